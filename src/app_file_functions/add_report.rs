@@ -0,0 +1,279 @@
+use crate::app_file_functions::format_app_files::format_app_text;
+use crate::app_file_functions::list_app_files;
+use crate::app_file_functions::migrate_app_files::{
+    has_every_key, key_value_lines, migrate_app_text, split_top_level_entries, Entry,
+};
+use crate::stdin_functions::get_string_from_user::get_string_from_user;
+use crate::structs::app_compat_app::{AppCompatApp, PACKAGE_NAME_TEXT};
+use crate::structs::app_file_scan_options::AppFileScanOptions;
+use crate::structs::app_report::AppReport;
+use crate::structs::string_or_none::StringOrNone;
+use serde_yaml::{Mapping, Value};
+use std::fs::{read, write};
+use std::path::{Path, PathBuf};
+
+// ask for a new report and add it to an existing `package_name.yaml`, used by `--add-report`
+// the file is found the same way `--fmt` and `--migrate` find app files, so it can be in a
+// category's subfolder too. files with an older schema version are migrated first
+pub fn add_report(folder: &Path, scan: &AppFileScanOptions) -> Result<(), String> {
+    let package_name = get_string_from_user(&format!("[required] {}:", PACKAGE_NAME_TEXT), false)?;
+
+    let mut paths = list_app_files(folder, scan)?
+        .into_iter()
+        .filter(|path| path.file_stem().is_some_and(|stem| *stem == *package_name))
+        .collect::<Vec<PathBuf>>();
+    let path = match paths.len() {
+        1 => paths.remove(0),
+        0 => {
+            return Err(format!(
+                "there's no app file for {} in {}. Use --add to add the app first.",
+                package_name,
+                folder.display()
+            ))
+        }
+        _ => {
+            return Err(format!(
+                "there are several app files for {}: {}",
+                package_name,
+                paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))
+        }
+    };
+
+    let bytes = read(&path).map_err(|e| format!("error opening {}: {}", path.display(), e))?;
+    let app = AppCompatApp::new_from_yaml_bytes(&bytes, &path)?;
+    let text = match migrate_app_text(&bytes, &path)? {
+        Some(text) => {
+            println!("Migrated {}", path.display());
+            text
+        }
+        None => String::from_utf8_lossy(&bytes).to_string(),
+    };
+
+    // the first report would hide the app's own result, so that one becomes a report too
+    // if it says when it was tested
+    let mut added = vec![];
+    if app.reports.is_empty() {
+        if let StringOrNone(Some(tested_on)) = &app.tested_on {
+            added.push(AppReport {
+                tested_on: tested_on.clone(),
+                version: app.version.clone(),
+                status: app.status,
+                comment: app.other_compatibility_comment.clone(),
+                tester: app.tester.clone(),
+                os_build: app.os_build.clone(),
+                device_model: app.device_model.clone(),
+                user_profile: app.user_profile,
+            });
+        }
+    }
+
+    added.push(AppReport::new_from_command_line()?);
+
+    let new_text = add_reports_to_text(&text, &app.reports, &added, &path)?;
+    write(&path, new_text).map_err(|e| format!("error writing {}: {}", path.display(), e))?;
+
+    println!("Added the report to {}", path.display());
+    Ok(())
+}
+
+// the file with `added` at the end of its `reports`, `reports` are the ones it has already
+// like `--migrate`, the file is edited line by line, so its comments and key order stay:
+// - the new reports are added below a `reports:` list, with the same indentation
+// - `reports: []` or a flow list is written again, with serde's layout
+// - if there's no `reports` yet, it's added at the end, above the comments at the end of the file
+fn add_reports_to_text(
+    text: &str,
+    reports: &[AppReport],
+    added: &[AppReport],
+    path: &Path,
+) -> Result<String, String> {
+    let raw: Value = serde_yaml::from_str(text)
+        .map_err(|e| format!("error deserializing {}: {}", path.display(), e))?;
+    let all = reports
+        .iter()
+        .chain(added)
+        .cloned()
+        .collect::<Vec<AppReport>>();
+
+    let mut entries = split_top_level_entries(text);
+
+    // files that can't be read line by line are written like `--fmt` does instead
+    if !has_every_key(&raw, &entries) {
+        let mut app = AppCompatApp::new_from_yaml_bytes(text.as_bytes(), path)?;
+        app.reports = all;
+        return format_app_text(&app, text, path);
+    }
+
+    let mut reports_mapping = Mapping::new();
+    reports_mapping.insert(
+        Value::from("reports"),
+        serde_yaml::to_value(&all).map_err(|e| e.to_string())?,
+    );
+    let reports_lines = key_value_lines(&reports_mapping, "reports").unwrap_or_default();
+
+    match entries
+        .iter_mut()
+        .find(|entry| entry.key.as_deref() == Some("reports"))
+    {
+        Some(entry) => {
+            let (key_line, value) = entry.lines[entry.leading..]
+                .split_first()
+                .expect("an entry with a key has the key's line");
+            // `reports:` with only a comment after it, and the list on the lines below
+            let block_list = key_line
+                .split_once(':')
+                .is_some_and(|(_, rest)| rest.trim().is_empty() || rest.trim().starts_with('#'));
+            let indent = value
+                .iter()
+                .find(|line| line.trim_start().starts_with('-'))
+                .map(|line| line.len() - line.trim_start().len());
+
+            match (block_list, indent) {
+                (true, Some(indent)) => {
+                    let added_lines = serde_yaml::to_string(added).map_err(|e| e.to_string())?;
+                    entry.lines.extend(
+                        added_lines
+                            .lines()
+                            .map(|line| format!("{}{}", " ".repeat(indent), line)),
+                    );
+                }
+                _ => {
+                    entry.lines.truncate(entry.leading);
+                    entry.lines.extend(reports_lines);
+                }
+            }
+        }
+        None => {
+            let position = entries.len()
+                - entries
+                    .iter()
+                    .rev()
+                    .take_while(|entry| entry.key.is_none())
+                    .count();
+            entries.insert(
+                position,
+                Entry {
+                    key: Some("reports".to_string()),
+                    leading: 0,
+                    lines: reports_lines,
+                },
+            );
+        }
+    }
+
+    let mut new_text = entries
+        .into_iter()
+        .flat_map(|entry| entry.lines)
+        .collect::<Vec<String>>()
+        .join("\n");
+    new_text.push('\n');
+
+    // make sure every report made it into the file
+    let app = AppCompatApp::new_from_yaml_bytes(new_text.as_bytes(), path)
+        .map_err(|e| format!("adding the report to {} went wrong: {}", path.display(), e))?;
+    if app.reports.len() != all.len() {
+        return Err(format!(
+            "adding the report to {} went wrong: it has {} report(s) instead of {}",
+            path.display(),
+            app.reports.len(),
+            all.len()
+        ));
+    }
+
+    Ok(new_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::app_compat_app::CURRENT_SCHEMA_VERSION;
+    use crate::structs::compat_status::CompatStatus;
+
+    fn report(tested_on: &str) -> AppReport {
+        AppReport {
+            tested_on: tested_on.to_string(),
+            version: "2".to_string(),
+            status: CompatStatus::Works,
+            comment: StringOrNone(None),
+            tester: StringOrNone(None),
+            os_build: StringOrNone(None),
+            device_model: StringOrNone(None),
+            user_profile: None,
+        }
+    }
+
+    fn add(text: &str, added: &[AppReport]) -> String {
+        let path = Path::new("app.yaml");
+        let app = AppCompatApp::new_from_yaml_bytes(text.as_bytes(), path).unwrap();
+        add_reports_to_text(text, &app.reports, added, path).unwrap()
+    }
+
+    fn app_text(reports: &str) -> String {
+        format!(
+            "# yaml-language-server: $schema=app.schema.json\n# header\nschema_version: {}\napp_name: x # the name\npackage_name: com.x\nversion: '1'\nstatus: works\n{}",
+            CURRENT_SCHEMA_VERSION, reports
+        )
+    }
+
+    #[test]
+    fn reports_are_added_below_a_list_with_its_indentation() {
+        let text = app_text(
+            "# every test\nreports:\n  # the first one\n  - tested_on: 2024-01-01\n    version: '1'\n    status: works\n# the end\n",
+        );
+
+        assert_eq!(
+            add(&text, &[report("2024-02-01")]),
+            app_text("# every test\nreports:\n  # the first one\n  - tested_on: 2024-01-01\n    version: '1'\n    status: works\n  - tested_on: 2024-02-01\n    version: '2'\n    status: works\n# the end\n")
+        );
+    }
+
+    #[test]
+    fn empty_and_flow_lists_are_written_again() {
+        let expected =
+            app_text("reports:\n- tested_on: 2024-02-01\n  version: '2'\n  status: works\n");
+
+        assert_eq!(
+            add(&app_text("reports: []\n"), &[report("2024-02-01")]),
+            expected
+        );
+        assert_eq!(
+            add(
+                &app_text("reports: [{ tested_on: 2024-01-01, version: '1', status: works }]\n"),
+                &[report("2024-02-01")]
+            ),
+            app_text("reports:\n- tested_on: 2024-01-01\n  version: '1'\n  status: works\n- tested_on: 2024-02-01\n  version: '2'\n  status: works\n")
+        );
+    }
+
+    #[test]
+    fn missing_reports_go_above_the_comments_at_the_end() {
+        assert_eq!(
+            add(&app_text("\n# the end\n"), &[report("2024-02-01")]),
+            app_text(
+                "reports:\n- tested_on: 2024-02-01\n  version: '2'\n  status: works\n\n# the end\n"
+            )
+        );
+    }
+
+    #[test]
+    fn flow_mappings_are_written_like_fmt() {
+        let text = format!(
+            "# yaml-language-server: $schema=app.schema.json\n{{ schema_version: {}, app_name: x, package_name: com.x, version: '1', status: works }}\n",
+            CURRENT_SCHEMA_VERSION
+        );
+        let added = add(&text, &[report("2024-02-01")]);
+
+        assert!(added.starts_with(&format!(
+            "# yaml-language-server: $schema=app.schema.json\nschema_version: {}\n",
+            CURRENT_SCHEMA_VERSION
+        )));
+        assert!(
+            added.ends_with("reports:\n- tested_on: 2024-02-01\n  version: '2'\n  status: works\n")
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::app_file_functions::list_app_files;
+use crate::structs::app_compat_app::AppCompatApp;
+use crate::structs::app_file_scan_options::AppFileScanOptions;
+use similar::TextDiff;
+use std::fs::{read, write};
+use std::path::Path;
+
+// `# yaml-language-server: $schema=app.schema.json` points editors to the json schema
+const SCHEMA_MODELINE: &str = "# yaml-language-server:";
+
+// rewrite every app file in the same layout `AppCompatApp::save_to_file` uses, used by `--fmt`
+// with `check`, nothing is written, the differences are printed instead and
+// it's an error if any file isn't formatted
+pub fn format_app_files(
+    folder: &Path,
+    scan: &AppFileScanOptions,
+    check: bool,
+) -> Result<(), String> {
+    let mut changed = 0;
+    let mut errors = vec![];
+
+    for f in list_app_files(folder, scan)? {
+        match format_app_file(&f, check) {
+            Ok(true) => changed += 1,
+            Ok(false) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    if check {
+        if changed > 0 {
+            return Err(format!(
+                "{} app file(s) aren't formatted. Run with --fmt to fix them.",
+                changed
+            ));
+        }
+        println!("All app files are formatted.");
+    } else {
+        println!("Formatted {} app file(s).", changed);
+    }
+
+    Ok(())
+}
+
+// the app the way `--fmt` writes it, `text` is the file it was read from
+// the schema modeline at the top is the only comment that's kept, editors need it
+pub fn format_app_text(app: &AppCompatApp, text: &str, path: &Path) -> Result<String, String> {
+    let modeline = text
+        .lines()
+        .take_while(|line| line.starts_with('#'))
+        .filter(|line| line.starts_with(SCHEMA_MODELINE))
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+
+    Ok(modeline
+        + &serde_yaml::to_string(app)
+            .map_err(|e| format!("error formatting {}: {}", path.display(), e))?)
+}
+
+// returns whether the file is (or with `check`, would be) changed
+fn format_app_file(path: &Path, check: bool) -> Result<bool, String> {
+    let bytes = read(path).map_err(|e| format!("error opening {}: {}", path.display(), e))?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let mut app = AppCompatApp::new_from_yaml_bytes(&bytes, path)?;
+    app.trim_all_string_fields();
+    app.check_links(true)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let formatted = format_app_text(&app, &text, path)?;
+
+    if formatted == text {
+        return Ok(false);
+    }
+
+    if check {
+        let name = path.display().to_string();
+        print!(
+            "{}",
+            TextDiff::from_lines(text.as_ref(), &formatted)
+                .unified_diff()
+                .header(&name, &name)
+        );
+    } else {
+        write(path, formatted).map_err(|e| format!("error writing {}: {}", path.display(), e))?;
+        println!("Formatted {}", path.display());
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,378 @@
+use crate::app_file_functions::{list_app_files, top_level_key};
+use crate::structs::app_compat_app::{AppCompatApp, CURRENT_SCHEMA_VERSION};
+use crate::structs::app_file_scan_options::AppFileScanOptions;
+use serde_yaml::{Mapping, Value};
+use std::fs::{read, write};
+use std::path::Path;
+
+// keys that older schema versions had, and that are dropped when migrating
+// their values were already converted while reading the app
+const REMOVED_KEYS: [&str; 3] = [
+    "works",
+    "works_without_compat_mode",
+    "repo_or_download_link",
+];
+
+// rewrite app files that use an older schema version, used by `--migrate`
+// files that are already up to date aren't touched
+pub fn migrate_app_files(folder: &Path, scan: &AppFileScanOptions) -> Result<(), String> {
+    let mut migrated = 0;
+    let mut errors = vec![];
+
+    for f in list_app_files(folder, scan)? {
+        match migrate_app_file(&f) {
+            Ok(true) => {
+                println!("Migrated {}", f.display());
+                migrated += 1;
+            }
+            Ok(false) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    println!("Migrated {} app file(s).", migrated);
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok(())
+}
+
+// returns whether the file was rewritten
+fn migrate_app_file(path: &Path) -> Result<bool, String> {
+    let bytes = read(path).map_err(|e| format!("error opening {}: {}", path.display(), e))?;
+
+    let Some(new_text) = migrate_app_text(&bytes, path)? else {
+        return Ok(false);
+    };
+    write(path, new_text).map_err(|e| format!("error writing {}: {}", path.display(), e))?;
+
+    Ok(true)
+}
+
+// the migrated file, or `None` if it's up to date already
+// `path` is only used for error messages
+//
+// the file isn't just saved again with serde, because that would throw away comments
+// and the key order people chose. instead, it's edited line by line:
+// - keys that don't exist anymore are removed
+// - `schema_version` is set to the current version
+// - missing keys are added right after the key that comes before them in `AppCompatApp`
+// everything else stays exactly as it was
+pub fn migrate_app_text(bytes: &[u8], path: &Path) -> Result<Option<String>, String> {
+    let raw: Value = serde_yaml::from_slice(bytes)
+        .map_err(|e| format!("error deserializing {}: {}", path.display(), e))?;
+    let schema_version = raw
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(1);
+    if schema_version == CURRENT_SCHEMA_VERSION as u64 {
+        return Ok(None);
+    }
+
+    // reading the app converts everything to the current version,
+    // so this has all the keys with their new values in the right order
+    let app = AppCompatApp::new_from_yaml_bytes(bytes, path)?;
+    let current = match serde_yaml::to_value(&app).map_err(|e| e.to_string())? {
+        Value::Mapping(mapping) => mapping,
+        _ => return Err(format!("{} is not a yaml mapping", path.display())),
+    };
+
+    let text = std::str::from_utf8(bytes)
+        .map_err(|e| format!("{} is not valid utf-8: {}", path.display(), e))?;
+
+    let entries = split_top_level_entries(text);
+
+    // files that can't be read line by line are written like `--fmt` does instead
+    if !has_every_key(&raw, &entries) {
+        return serde_yaml::to_string(&app)
+            .map(Some)
+            .map_err(|e| format!("error migrating {}: {}", path.display(), e));
+    }
+
+    let mut entries = entries
+        .into_iter()
+        .filter_map(|mut entry| match entry.key.as_deref() {
+            Some(key) if REMOVED_KEYS.contains(&key) => {
+                // only keep the comments that were above it
+                entry.lines.truncate(entry.leading);
+                (!entry.lines.is_empty()).then_some(Entry { key: None, ..entry })
+            }
+            Some("schema_version") => {
+                entry.lines.truncate(entry.leading);
+                entry
+                    .lines
+                    .extend(key_value_lines(&current, "schema_version")?);
+                Some(entry)
+            }
+            _ => Some(entry),
+        })
+        .collect::<Vec<Entry>>();
+
+    // add the missing keys, in the same order as in `AppCompatApp`
+    let keys = current
+        .keys()
+        .filter_map(Value::as_str)
+        .collect::<Vec<&str>>();
+    for (i, key) in keys.iter().enumerate() {
+        if entries
+            .iter()
+            .any(|entry| entry.key.as_deref() == Some(key))
+        {
+            continue;
+        }
+
+        // right after the closest key before it that's in the file
+        let previous = keys[..i].iter().rev().find_map(|previous| {
+            entries
+                .iter()
+                .position(|entry| entry.key.as_deref() == Some(previous))
+        });
+        let mut lines = key_value_lines(&current, key).unwrap_or_default();
+        let mut leading = 0;
+        let position = match previous {
+            // the comments of a removed key that are right after it are usually about
+            // what replaces it, so those stay above the new key
+            // the comments at the end of the file stay at the end though
+            Some(position) => {
+                position
+                    + 1
+                    + entries[position + 1..]
+                        .iter()
+                        .take_while(|entry| entry.key.is_none())
+                        .count()
+                        .min(entries.len().saturating_sub(position + 2))
+            }
+            // or at the top, but below the comments at the top of the file
+            // those belong to the first key, but they're usually about the whole file
+            None => {
+                let position = entries
+                    .iter()
+                    .take_while(|entry| entry.key.is_none())
+                    .count();
+                if let Some(first) = entries.get_mut(position) {
+                    let mut header = first.lines.drain(..first.leading).collect::<Vec<String>>();
+                    first.leading = 0;
+                    leading = header.len();
+                    header.append(&mut lines);
+                    lines = header;
+                }
+                position
+            }
+        };
+
+        entries.insert(
+            position,
+            Entry {
+                key: Some(key.to_string()),
+                leading,
+                lines,
+            },
+        );
+    }
+
+    let mut new_text = entries
+        .into_iter()
+        .flat_map(|entry| entry.lines)
+        .collect::<Vec<String>>()
+        .join("\n");
+    new_text.push('\n');
+
+    // make sure nothing got lost on the way
+    AppCompatApp::new_from_yaml_bytes(new_text.as_bytes(), path)
+        .map_err(|e| format!("migrating {} went wrong: {}", path.display(), e))?;
+
+    Ok(Some(new_text))
+}
+
+// one top-level key with everything that belongs to it
+pub struct Entry {
+    // `None` for comments that aren't above any key, like at the end of the file
+    pub key: Option<String>,
+    // how many of the lines are comments or empty lines above the key
+    pub leading: usize,
+    pub lines: Vec<String>,
+}
+
+// split a flat yaml file into its top-level keys
+// comments and empty lines belong to the key below them
+pub fn split_top_level_entries(text: &str) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut pending = vec![];
+
+    for line in text.lines() {
+        if let Some(key) = top_level_key(line) {
+            let leading = pending.len();
+            pending.push(line.to_string());
+            entries.push(Entry {
+                key: Some(key.to_string()),
+                leading,
+                lines: std::mem::take(&mut pending),
+            });
+        } else if line.starts_with(|c: char| c.is_whitespace() || c == '-')
+            && !line.trim().is_empty()
+        {
+            // an indented line continues the value of the last key,
+            // so do the empty lines and comments right before it
+            match entries.last_mut() {
+                Some(entry) => {
+                    entry.lines.append(&mut pending);
+                    entry.lines.push(line.to_string());
+                }
+                None => pending.push(line.to_string()),
+            }
+        } else {
+            pending.push(line.to_string());
+        }
+    }
+
+    if !pending.is_empty() {
+        entries.push(Entry {
+            key: None,
+            leading: pending.len(),
+            lines: pending,
+        });
+    }
+
+    entries
+}
+
+// whether every key in the file was found by `split_top_level_entries`
+// it isn't for files that can't be read line by line, like a flow mapping `{ app_name: ... }`
+pub fn has_every_key(raw: &Value, entries: &[Entry]) -> bool {
+    raw.as_mapping().is_some_and(|mapping| {
+        mapping.keys().filter_map(Value::as_str).all(|key| {
+            entries
+                .iter()
+                .any(|entry| entry.key.as_deref() == Some(key))
+        })
+    })
+}
+
+// `key: value` the way serde would write it
+pub fn key_value_lines(mapping: &Mapping, key: &str) -> Option<Vec<String>> {
+    let value = mapping.get(key)?;
+
+    let mut single = Mapping::new();
+    single.insert(Value::from(key), value.clone());
+
+    serde_yaml::to_string(&single)
+        .ok()
+        .map(|yaml| yaml.lines().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate(text: &str) -> String {
+        migrate_app_text(text.as_bytes(), Path::new("app.yaml"))
+            .unwrap()
+            .unwrap()
+    }
+
+    // everything a migrated file gets, after `other_compatibility_comment`
+    const NEW_KEYS: &str = "tested_on: null
+os_build: null
+device_model: null
+user_profile: null
+tester: null
+reports: []
+";
+
+    #[test]
+    fn entries_keep_comments_block_scalars_and_lists_with_their_key() {
+        let entries = split_top_level_entries(
+            "# header\n\n# about the name\napp_name: x\ndescription: |-\n  first\n\n  second\nlinks:\n- kind: play\n  url: null\n# trailing\n",
+        );
+
+        let keys = entries
+            .iter()
+            .map(|entry| entry.key.as_deref())
+            .collect::<Vec<Option<&str>>>();
+        assert_eq!(
+            keys,
+            [Some("app_name"), Some("description"), Some("links"), None]
+        );
+        assert_eq!(entries[0].leading, 3);
+        assert_eq!(
+            entries[1].lines,
+            ["description: |-", "  first", "", "  second"]
+        );
+        assert_eq!(entries[2].lines, ["links:", "- kind: play", "  url: null"]);
+        assert_eq!(entries[3].lines, ["# trailing"]);
+    }
+
+    #[test]
+    fn key_value_lines_are_written_like_serde() {
+        let mut mapping = Mapping::new();
+        mapping.insert(Value::from("tags"), Value::from(vec!["a", "b"]));
+        mapping.insert(Value::from("comment"), Value::from("one\ntwo"));
+
+        assert_eq!(
+            key_value_lines(&mapping, "tags").unwrap(),
+            ["tags:", "- a", "- b"]
+        );
+        assert_eq!(
+            key_value_lines(&mapping, "comment").unwrap(),
+            ["comment: |-", "  one", "  two"]
+        );
+        assert_eq!(key_value_lines(&mapping, "missing"), None);
+    }
+
+    #[test]
+    fn schema_version_goes_below_the_header_comment_and_new_keys_above_the_last_one() {
+        let migrated = migrate(
+            "# a comment\n\napp_name: x\npackage_name: com.x\nversion: '1'\nrepo_or_download_link: https://github.com/x/x\nworks: true\nworks_without_compat_mode: true\nworks_without_gms: null\nworks_installed_by_any_source: null\nother_compatibility_comment: null\n# the end\n",
+        );
+
+        assert_eq!(
+            migrated,
+            format!(
+                "# a comment\n\nschema_version: {}\napp_name: x\npackage_name: com.x\nversion: '1'\nlinks:\n- kind: source\n  url: https://github.com/x/x\ndescription: null\ncategory: null\ntags: []\nstatus: works\nworks_without_gms: null\nworks_installed_by_any_source: null\nother_compatibility_comment: null\n{}# the end\n",
+                CURRENT_SCHEMA_VERSION, NEW_KEYS
+            )
+        );
+    }
+
+    #[test]
+    fn comments_block_scalars_and_lists_are_kept() {
+        let migrated = migrate(
+            "schema_version: 4\napp_name: x\npackage_name: com.x\nversion: '1'\n# the store\nrepo_or_download_link: https://play.google.com/store/apps/details?id=com.x\ndescription: |-\n  first\n\n  second\ncategory: null\n# why these tags\ntags:\n- banking\n- nfc\nstatus: works\nworks_without_gms: null\nworks_installed_by_any_source: null\nother_compatibility_comment: null # nothing yet\n",
+        );
+
+        assert_eq!(
+            migrated,
+            format!(
+                "schema_version: {}\napp_name: x\npackage_name: com.x\nversion: '1'\n# the store\nlinks:\n- kind: play\n  url: https://play.google.com/store/apps/details?id=com.x\ndescription: |-\n  first\n\n  second\ncategory: null\n# why these tags\ntags:\n- banking\n- nfc\nstatus: works\nworks_without_gms: null\nworks_installed_by_any_source: null\nother_compatibility_comment: null # nothing yet\n{}",
+                CURRENT_SCHEMA_VERSION, NEW_KEYS
+            )
+        );
+    }
+
+    #[test]
+    fn up_to_date_files_are_left_alone() {
+        let text = format!(
+            "schema_version: {}\napp_name: x\npackage_name: com.x\nversion: '1'\nstatus: works\n",
+            CURRENT_SCHEMA_VERSION
+        );
+
+        assert_eq!(
+            migrate_app_text(text.as_bytes(), Path::new("app.yaml")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn flow_mappings_are_rewritten_with_serde() {
+        let migrated =
+            migrate("{ app_name: x, package_name: com.x, version: '1', works: false }\n");
+
+        assert!(migrated.starts_with(&format!(
+            "schema_version: {}\napp_name: x\n",
+            CURRENT_SCHEMA_VERSION
+        )));
+        assert!(migrated.contains("status: does_not_work\n"));
+    }
+}
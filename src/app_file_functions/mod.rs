@@ -0,0 +1,179 @@
+pub mod add_report;
+pub mod format_app_files;
+pub mod migrate_app_files;
+
+use crate::structs::app_file_scan_options::AppFileScanOptions;
+use crate::structs::app_output_md_file_config::AppOutputMdFileConfig;
+use crate::structs::command_line_opts::CommandLineOpts;
+use glob::{MatchOptions, Pattern};
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use strsim::levenshtein;
+
+// all app files in the folder, sorted so that skipped files, errors and
+// the list itself always come out in the same order
+pub fn list_app_files(folder: &Path, scan: &AppFileScanOptions) -> Result<Vec<PathBuf>, String> {
+    let include = compile_patterns(&scan.include)?;
+    let exclude = compile_patterns(&scan.exclude)?;
+
+    let mut files = vec![];
+    list_app_files_in(folder, folder, scan, &include, &exclude, &mut files)?;
+    files.sort();
+
+    Ok(files)
+}
+
+fn list_app_files_in(
+    root: &Path,
+    folder: &Path,
+    scan: &AppFileScanOptions,
+    include: &[Pattern],
+    exclude: &[Pattern],
+    files: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let dir = read_dir(folder)
+        .map_err(|e| format!("unable to read the folder {}: {}", folder.display(), e))?;
+
+    let mut paths = dir
+        .map(|file| file.map(|f| f.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .map_err(|e| format!("error getting file from directory list: {}", e))?;
+    paths.sort();
+
+    for path in paths {
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let skip = |reason: &str| {
+            if !scan.quiet {
+                eprintln!("Skipping {} ({})", relative, reason);
+            }
+        };
+
+        if matches_any(exclude, &path, &relative) {
+            skip("excluded");
+        } else if path.is_dir() {
+            if scan.recursive {
+                list_app_files_in(root, &path, scan, include, exclude, files)?;
+            } else {
+                skip("folder");
+            }
+        } else if !matches_any(include, &path, &relative) {
+            skip("not included");
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>, String> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern).map_err(|e| format!("invalid pattern `{}`: {}", pattern, e))
+        })
+        .collect()
+}
+
+fn matches_any(patterns: &[Pattern], path: &Path, relative: &str) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+
+    patterns.iter().any(|pattern| {
+        if pattern.as_str().contains('/') {
+            pattern.matches_with(relative, options)
+        } else {
+            pattern.matches_with(&name, options)
+        }
+    })
+}
+
+// the scan options from the config if there is one, otherwise the defaults
+// used by the commands that work on app files without needing the config
+pub fn scan_options_from_opts(opt: &CommandLineOpts) -> Result<AppFileScanOptions, String> {
+    let mut scan = match &opt.yaml_file {
+        Some(yaml_file) => AppOutputMdFileConfig::new_from_file(yaml_file)?.app_files,
+        None => AppFileScanOptions::default(),
+    };
+    scan.quiet = opt.quiet;
+
+    Ok(scan)
+}
+
+// the key if this line starts a top-level `key: value` in an app file
+// app files are flat, so this is all that's needed to find their keys line by line
+// quoted keys like `"app_name": x` are unquoted, and flow mappings like `{ app_name: x }`
+// aren't lines with a key at all
+pub fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with(|c: char| c.is_whitespace() || "#-{[?%&*!|>".contains(c)) {
+        return None;
+    }
+
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = line[1..].find(quote)? + 1;
+            (&line[1..end], &line[end + 1..])
+        }
+        _ => {
+            let key = line.split_once(':')?.0;
+            (key.trim(), &line[key.len()..])
+        }
+    };
+
+    // like yaml, `key:value` without a space is a plain value and not a key
+    let value = rest.trim_start().strip_prefix(':')?;
+    (value.is_empty() || value.starts_with(char::is_whitespace))
+        .then_some(key)
+        .filter(|key| !key.is_empty())
+}
+
+// `, did you mean `...`?` with the closest valid key, or the list of all valid keys
+// if none of them are close enough to be a typo
+pub fn did_you_mean(key: &str, valid_keys: &[&str]) -> String {
+    let closest = valid_keys
+        .iter()
+        .map(|valid| (levenshtein(key, valid), valid))
+        .min();
+
+    match closest {
+        Some((distance, valid)) if distance <= (key.len() / 3).max(2) => {
+            format!(", did you mean `{}`?", valid)
+        }
+        _ => format!(", expected one of `{}`", valid_keys.join("`, `")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_level_key_finds_plain_and_quoted_keys() {
+        assert_eq!(top_level_key("app_name: beta"), Some("app_name"));
+        assert_eq!(top_level_key("app_name:"), Some("app_name"));
+        assert_eq!(top_level_key("\"app_name\": beta"), Some("app_name"));
+        assert_eq!(top_level_key("'app_name' : beta"), Some("app_name"));
+        assert_eq!(top_level_key("\"a: b\": beta"), Some("a: b"));
+    }
+
+    #[test]
+    fn top_level_key_skips_everything_else() {
+        assert_eq!(top_level_key("  nested: beta"), None);
+        assert_eq!(top_level_key("- kind: play"), None);
+        assert_eq!(top_level_key("# app_name: beta"), None);
+        assert_eq!(top_level_key("{ app_name: beta }"), None);
+        assert_eq!(top_level_key("url:https://example.com"), None);
+        assert_eq!(top_level_key("---"), None);
+        assert_eq!(top_level_key(""), None);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::output_functions::check_output_file::check_output_file;
+use crate::output_functions::write_output_file::write_output_file;
+use crate::structs::app_compat_list::AppCompatList;
+use crate::structs::app_output_md_file_config::AppOutputMdFileConfig;
+use crate::structs::app_page_options::GENERATED_PAGE_MARKER;
+use std::collections::HashSet;
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_file};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// zola uses this file for the section's own page
+const INDEX_PAGE: &str = "_index.md";
+
+// write a page for every app and an index page linking them into `output_dir`, used by `--output-dir`
+// pages of apps that don't exist anymore are removed
+// with `check`, nothing is written and it's an error if anything would change
+pub fn build_app_pages(
+    output_dir: &Path,
+    config: &AppOutputMdFileConfig,
+    apps: &AppCompatList,
+    check: bool,
+) -> Result<(), String> {
+    if !check {
+        create_dir_all(output_dir)
+            .map_err(|e| format!("unable to create {}: {}", output_dir.display(), e))?;
+    }
+
+    // only the paths are collected up front, every page is rendered right before it's written
+    let apps_with_categories = apps.apps().collect::<Vec<_>>();
+    let mut paths = apps_with_categories
+        .iter()
+        .map(|(app, _)| output_dir.join(format!("{}.md", app.anchor())))
+        .collect::<Vec<PathBuf>>();
+    paths.push(output_dir.join(INDEX_PAGE));
+
+    write_pages(
+        output_dir,
+        "",
+        &paths,
+        check,
+        |i, w| match apps_with_categories.get(i) {
+            Some((app, category)) => config.app_pages.write_page(app, *category, w),
+            None => config.write_index_page(apps, w),
+        },
+    )
+}
+
+// write generated pages into `output_dir`, or only compare them with `check`
+// `render` writes the page at that index in `paths`, one page at a time
+// generated pages whose names start with `prefix` that aren't in `paths` anymore are removed
+pub fn write_pages<F>(
+    output_dir: &Path,
+    prefix: &str,
+    paths: &[PathBuf],
+    check: bool,
+    render: F,
+) -> Result<(), String>
+where
+    F: Fn(usize, &mut dyn Write) -> io::Result<()>,
+{
+    let expected = paths.iter().cloned().collect::<HashSet<PathBuf>>();
+    let stale = stale_pages(output_dir, prefix, &expected)?;
+
+    if check {
+        // report everything that's out of date at once
+        let mut errors = vec![];
+        for (i, path) in paths.iter().enumerate() {
+            // the diff needs the whole page anyway, but only this one is in memory
+            let mut page = vec![];
+            render(i, &mut page).map_err(|e| e.to_string())?;
+            if let Err(e) = check_output_file(path, page) {
+                errors.push(e);
+            }
+        }
+        errors.extend(stale.iter().map(|path| not_needed_anymore(path)));
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        println!("The pages in {} are up to date.", output_dir.display());
+        return Ok(());
+    }
+
+    let mut written = 0;
+    for (i, path) in paths.iter().enumerate() {
+        if write_output_file(path, |w| render(i, w))? {
+            written += 1;
+        }
+    }
+    for path in stale.iter() {
+        remove_file(path).map_err(|e| format!("unable to remove {}: {}", path.display(), e))?;
+    }
+
+    // stderr, so it doesn't end up in the list when that's written to stdout
+    eprintln!(
+        "Wrote {} of {} page(s) in {}, removed {} old page(s).",
+        written,
+        paths.len(),
+        output_dir.display(),
+        stale.len()
+    );
+    Ok(())
+}
+
+// remove every generated page whose name starts with `prefix`, like the pages of a list
+// that isn't split anymore. with `check`, it's an error if there are any
+pub fn remove_stale_pages(output_dir: &Path, prefix: &str, check: bool) -> Result<(), String> {
+    let stale = stale_pages(output_dir, prefix, &HashSet::new())?;
+
+    if check {
+        if !stale.is_empty() {
+            return Err(stale
+                .iter()
+                .map(|path| not_needed_anymore(path))
+                .collect::<Vec<String>>()
+                .join("\n"));
+        }
+        return Ok(());
+    }
+
+    for path in stale.iter() {
+        remove_file(path).map_err(|e| format!("unable to remove {}: {}", path.display(), e))?;
+    }
+    if !stale.is_empty() {
+        eprintln!(
+            "Removed {} old page(s): {}",
+            stale.len(),
+            stale
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn not_needed_anymore(path: &Path) -> String {
+    format!(
+        "{} isn't needed anymore. Run with --run to remove it.",
+        path.display()
+    )
+}
+
+// pages this program made earlier that aren't made anymore
+// anything else in the folder is left alone
+fn stale_pages(
+    output_dir: &Path,
+    prefix: &str,
+    expected: &HashSet<PathBuf>,
+) -> Result<Vec<PathBuf>, String> {
+    if !output_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut stale = vec![];
+    for entry in read_dir(output_dir)
+        .map_err(|e| format!("unable to read the folder {}: {}", output_dir.display(), e))?
+    {
+        let path = entry
+            .map_err(|e| format!("error getting file from directory list: {}", e))?
+            .path();
+        let has_prefix = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with(prefix));
+        if expected.contains(&path) || !has_prefix || path.extension().is_none_or(|ext| ext != "md")
+        {
+            continue;
+        }
+
+        let is_generated = read_to_string(&path)
+            .is_ok_and(|page| page.lines().any(|line| line == GENERATED_PAGE_MARKER));
+        if is_generated {
+            stale.push(path);
+        }
+    }
+    stale.sort();
+
+    Ok(stale)
+}
@@ -0,0 +1,119 @@
+use crate::build_functions::build_app_pages::build_app_pages;
+use crate::build_functions::build_split_pages::{build_split_pages, remove_split_pages};
+use crate::output_functions::check_output_file::check_output_file;
+use crate::output_functions::is_stdout;
+use crate::output_functions::write_output_file::write_output_file;
+use crate::structs::app_compat_list::AppCompatList;
+use crate::structs::app_output_md_file_config::AppOutputMdFileConfig;
+use crate::structs::build_cache::BuildCache;
+use crate::structs::command_line_opts::CommandLineOpts;
+use crate::structs::split_options::SplitBy;
+use std::fs::read;
+use std::io::{self, Write};
+use std::path::Path;
+
+// build the `.md` file for `--run`, or only compare it with the existing one for `--check`
+// with `--output-dir`, every app's page is built too
+pub fn build_md_file(opt: &CommandLineOpts) -> Result<(), String> {
+    if opt.output_md_file.is_none() && opt.output_dir.is_none() {
+        return Err("Cannot run without an --output-md-file or --output-dir.".to_string());
+    }
+
+    let (yaml_file, apps) = load_config_and_apps(opt)?;
+
+    if let Some(output_md_file) = &opt.output_md_file {
+        match yaml_file.split.by {
+            SplitBy::None => {
+                write_or_check(output_md_file, opt.check, |md_file| {
+                    yaml_file.write_md_file(&apps, md_file)
+                })?;
+                // pages from when the list was split would still be published otherwise
+                remove_split_pages(output_md_file, opt.check)?;
+            }
+            _ => build_split_pages(output_md_file, &yaml_file, &apps, opt.check)?,
+        }
+    }
+
+    if let Some(permalink_index) = &opt.permalink_index {
+        write_or_check(permalink_index, opt.check, |index| {
+            apps.write_permalink_index(index)
+        })?;
+    }
+
+    if let Some(output_dir) = &opt.output_dir {
+        build_app_pages(output_dir, &yaml_file, &apps, opt.check)?;
+    }
+
+    Ok(())
+}
+
+// write the file, or with `--check` only compare it with the existing one
+fn write_or_check<F>(path: &Path, check: bool, render: F) -> Result<(), String>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    if !check {
+        if !write_output_file(path, render)? {
+            eprintln!("{} is already up to date, not writing it", path.display());
+        }
+        return Ok(());
+    }
+
+    if is_stdout(path) {
+        return Err("--check needs a real output file to compare against.".to_string());
+    }
+
+    // the diff needs the whole file anyway, so render it into memory
+    let mut contents = vec![];
+    render(&mut contents).map_err(|e| e.to_string())?;
+    check_output_file(path, contents)?;
+
+    println!("{} is up to date.", path.display());
+    Ok(())
+}
+
+// read the config and all app files, sorted and ready to be printed
+// shared by everything that renders the list
+pub fn load_config_and_apps(
+    opt: &CommandLineOpts,
+) -> Result<(AppOutputMdFileConfig, AppCompatList), String> {
+    // check to make sure folders and files are valid
+    if !opt.yaml_file.clone().is_some_and(|file| file.is_file()) || !opt.app_files_folder().is_dir()
+    {
+        return Err("Cannot run due to one of the paths provided not being valid. Please check your paths and try again.".to_string());
+    }
+
+    // get the data from the yaml file
+    let yaml_file_path = opt.yaml_file.clone().unwrap();
+    let yaml_file = AppOutputMdFileConfig::new_from_file(&yaml_file_path)?;
+    let mut scan = yaml_file.app_files.clone();
+    scan.quiet = opt.quiet;
+
+    let mut cache = if opt.no_cache {
+        BuildCache::disabled()
+    } else {
+        let yaml_file_contents = read(&yaml_file_path).map_err(|e| e.to_string())?;
+        BuildCache::load(&opt.cache_file, &yaml_file_contents)
+    };
+
+    let mut apps = AppCompatList::new_from_folder(
+        opt.app_files_folder().to_path_buf(),
+        &scan,
+        yaml_file.fix_missing_https,
+        yaml_file.report_aggregation,
+        &cache,
+    )?;
+    apps.check_tags(&yaml_file.tags)?;
+
+    apps.update_cache(&mut cache);
+    cache.save(&opt.cache_file)?;
+
+    apps.filter(&yaml_file.filter);
+
+    // sorting apps here because they only need to be sorted if printing something,
+    // otherwise it doesn't matter if they're out of order during a simple check
+    apps.sort_list(yaml_file.group_by, &yaml_file.sorting)?;
+    apps.check_anchors(&yaml_file.tags)?;
+
+    Ok((yaml_file, apps))
+}
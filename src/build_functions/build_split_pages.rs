@@ -0,0 +1,68 @@
+use crate::build_functions::build_app_pages::{remove_stale_pages, write_pages};
+use crate::output_functions::is_stdout;
+use crate::structs::app_compat_list::{AppCompatList, ListPage};
+use crate::structs::app_output_md_file_config::AppOutputMdFileConfig;
+use std::path::{Path, PathBuf};
+
+// write the list spread over several pages, used instead of one `.md` file when the config
+// has a `split`
+// the first page is `output_md_file` itself, the others are next to it,
+// like `apps-b.md` or `apps-2.md`
+pub fn build_split_pages(
+    output_md_file: &Path,
+    config: &AppOutputMdFileConfig,
+    apps: &AppCompatList,
+    check: bool,
+) -> Result<(), String> {
+    if is_stdout(output_md_file) {
+        return Err("Splitting the list into pages needs a real --output-md-file.".to_string());
+    }
+
+    let (output_dir, stem) = split_pages_location(output_md_file)?;
+
+    let list_pages = apps.split(&config.split);
+    let file_stem = |i: usize| match i {
+        0 => stem.clone(),
+        _ => format!("{}-{}", stem, list_pages[i].name),
+    };
+    // zola puts every page in its own folder, next to each other
+    let page_url = |page: &ListPage| {
+        let i = list_pages
+            .iter()
+            .position(|p| p.name == page.name)
+            .unwrap_or_default();
+        format!("../{}/", file_stem(i))
+    };
+
+    let paths = (0..list_pages.len())
+        .map(|i| output_dir.join(format!("{}.md", file_stem(i))))
+        .collect::<Vec<PathBuf>>();
+    write_pages(&output_dir, &format!("{}-", stem), &paths, check, |i, w| {
+        config.write_md_page(apps, &list_pages, i, &page_url, w)
+    })
+}
+
+// remove the other pages of a list that was split before, used when it's one page again
+pub fn remove_split_pages(output_md_file: &Path, check: bool) -> Result<(), String> {
+    if is_stdout(output_md_file) {
+        return Ok(());
+    }
+
+    let (output_dir, stem) = split_pages_location(output_md_file)?;
+    remove_stale_pages(&output_dir, &format!("{}-", stem), check)
+}
+
+// the folder the pages are in and the name they all start with
+fn split_pages_location(output_md_file: &Path) -> Result<(PathBuf, String), String> {
+    let stem = output_md_file
+        .file_stem()
+        .ok_or_else(|| format!("{} is not a valid file name", output_md_file.display()))?
+        .to_string_lossy()
+        .to_string();
+    let output_dir = match output_md_file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    Ok((output_dir.to_path_buf(), stem))
+}
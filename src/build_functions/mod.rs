@@ -0,0 +1,5 @@
+pub mod build_app_pages;
+pub mod build_md_file;
+pub mod build_split_pages;
+pub mod serve;
+pub mod watch;
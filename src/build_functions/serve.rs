@@ -0,0 +1,101 @@
+use crate::build_functions::build_md_file::load_config_and_apps;
+use crate::build_functions::watch::watch_for_changes;
+use crate::output_functions::escape_html;
+use crate::structs::command_line_opts::CommandLineOpts;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+// the page asks for this every second and reloads itself when the number changes
+const GENERATION_PATH: &str = "/__generation";
+
+// serve a preview of the list as html, used by `--serve`
+// every page load reads the app files again, so it's always up to date,
+// and open pages reload themselves when something changes
+pub fn serve(opt: &CommandLineOpts) -> Result<(), String> {
+    // bumped every time a watched file changes
+    let generation = Arc::new(AtomicU64::new(0));
+
+    let watch_opt = opt.clone();
+    let watch_generation = generation.clone();
+    thread::spawn(move || {
+        let r = watch_for_changes(
+            &watch_opt,
+            || {},
+            || {
+                watch_generation.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+        if let Err(e) = r {
+            eprintln!("Error: {}. Pages won't reload by themselves.", e);
+        }
+    });
+
+    let address = format!("127.0.0.1:{}", opt.port);
+    let server =
+        Server::http(&address).map_err(|e| format!("unable to listen on {}: {}", address, e))?;
+    println!(
+        "Serving a preview on http://{}/ Press Ctrl+C to stop.",
+        address
+    );
+
+    for request in server.incoming_requests() {
+        let current_generation = generation.load(Ordering::SeqCst);
+
+        // ignore any query string
+        let path = request.url().split('?').next().unwrap_or_default();
+
+        let response = match path {
+            GENERATION_PATH => Response::from_string(current_generation.to_string()),
+            "/" => {
+                let (page, status) = match render_page(opt, current_generation) {
+                    Ok(page) => (page, 200),
+                    // show what's wrong right in the browser
+                    Err(e) => (render_error_page(&e, current_generation), 500),
+                };
+                Response::from_string(page)
+                    .with_status_code(status)
+                    .with_header(html_header())
+            }
+            _ => Response::from_string("Not found").with_status_code(404),
+        };
+
+        if let Err(e) = request.respond(response) {
+            eprintln!("Error sending a response: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+fn render_page(opt: &CommandLineOpts, generation: u64) -> Result<String, String> {
+    let (yaml_file, apps) = load_config_and_apps(opt)?;
+
+    let mut page = vec![];
+    yaml_file
+        .write_html_file(&apps, &reload_script(generation), &mut page)
+        .map_err(|e| e.to_string())?;
+
+    String::from_utf8(page).map_err(|e| e.to_string())
+}
+
+fn render_error_page(error: &str, generation: u64) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Error</title>\n</head>\n<body>\n<h1>Error</h1>\n<pre>{}</pre>\n{}\n</body>\n</html>\n",
+        escape_html(error),
+        reload_script(generation)
+    )
+}
+
+fn reload_script(generation: u64) -> String {
+    format!(
+        "<script>\nsetInterval(() => fetch(\"{}\").then(r => r.text()).then(g => {{ if (g !== \"{}\") location.reload(); }}).catch(() => {{}}), 1000);\n</script>",
+        GENERATION_PATH, generation
+    )
+}
+
+fn html_header() -> Header {
+    Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..])
+        .expect("the content type header is always valid")
+}
@@ -0,0 +1,223 @@
+use crate::build_functions::build_md_file::build_md_file;
+use crate::output_functions::is_stdout;
+use crate::structs::app_output_md_file_config::AppOutputMdFileConfig;
+use crate::structs::command_line_opts::CommandLineOpts;
+use notify_debouncer_mini::new_debouncer;
+use notify_debouncer_mini::notify::{RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+// editors and `git checkout` change a bunch of files at once,
+// so wait until it's been quiet for this long before rebuilding
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// rebuild the `.md` file every time an app file or the config changes, used by `--watch`
+// errors are printed, but never stop the watching
+pub fn watch(opt: &CommandLineOpts) -> Result<(), String> {
+    if opt.output_md_file.is_none() && opt.output_dir.is_none() {
+        return Err("Cannot watch without an --output-md-file or --output-dir.".to_string());
+    }
+    if opt.output_md_file.as_deref().is_some_and(is_stdout) {
+        return Err("--watch needs a real output file to write to.".to_string());
+    }
+
+    let mut watch_opt = opt.clone();
+    watch_opt.run = true;
+
+    watch_for_changes(opt, || rebuild(&watch_opt), || rebuild(&watch_opt))
+}
+
+// call `on_start` once everything is being watched, then `on_change` every time an app file,
+// the config or its markdown files change until the program is stopped
+pub fn watch_for_changes<S, F>(
+    opt: &CommandLineOpts,
+    on_start: S,
+    mut on_change: F,
+) -> Result<(), String>
+where
+    S: FnOnce(),
+    F: FnMut(),
+{
+    // there's nothing to watch if these are wrong from the start
+    let yaml_file = opt
+        .yaml_file
+        .clone()
+        .filter(|file| file.is_file())
+        .ok_or("Cannot watch without a valid --yaml-file.")?;
+    if !opt.app_files_folder().is_dir() {
+        return Err("Cannot watch because the app files folder isn't valid.".to_string());
+    }
+
+    let app_files_folder = canonicalize(opt.app_files_folder())?;
+    let yaml_file = canonicalize(&yaml_file)?;
+    let output_md_file = opt
+        .output_md_file
+        .as_deref()
+        .filter(|path| !is_stdout(path))
+        .map(absolute_path)
+        .transpose()?;
+    let output_dir = opt.output_dir.as_deref().map(absolute_path).transpose()?;
+    let permalink_index = opt
+        .permalink_index
+        .as_deref()
+        .filter(|path| !is_stdout(path))
+        .map(absolute_path)
+        .transpose()?;
+
+    // the config might be broken right now, which the first build will report,
+    // so only look into subfolders if it can be read. changing that needs a restart
+    let app_files_mode = match AppOutputMdFileConfig::new_from_file(&yaml_file) {
+        Ok(config) if config.app_files.recursive => RecursiveMode::Recursive,
+        _ => RecursiveMode::NonRecursive,
+    };
+
+    let (tx, rx) = channel();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, tx)
+        .map_err(|e| format!("unable to start watching: {}", e))?;
+    debouncer
+        .watcher()
+        .watch(&app_files_folder, app_files_mode)
+        .map_err(|e| format!("unable to watch {}: {}", app_files_folder.display(), e))?;
+    let mut watched = vec![(app_files_folder.clone(), app_files_mode)];
+
+    // the config, the tag vocabulary and the markdown files for the before and after text,
+    // including everything they include. their folders are watched instead of the files
+    // because editors usually save by replacing the file, which a watch on only the file
+    // itself wouldn't survive
+    let mut config_files = files_read_by_config(&yaml_file);
+    watch_folders_of(debouncer.watcher(), &config_files, &mut watched)?;
+
+    on_start();
+
+    println!(
+        "Watching {} and {} for changes. Press Ctrl+C to stop.",
+        app_files_folder.display(),
+        yaml_file.display()
+    );
+
+    for result in rx {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("Error while watching: {}", e);
+                continue;
+            }
+        };
+
+        let changed = events.iter().any(|event| {
+            let path = &event.path;
+
+            // dot files are skipped by the build anyway, and that's also where
+            // the cache and the output's temporary file live
+            let is_dot_file = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            // writing the output would trigger another build otherwise
+            let is_output = output_md_file.as_ref() == Some(path)
+                || output_md_file
+                    .as_deref()
+                    .is_some_and(|output| is_split_page(output, path))
+                || permalink_index.as_ref() == Some(path)
+                || output_dir.as_ref().is_some_and(|dir| path.starts_with(dir));
+
+            !is_dot_file
+                && !is_output
+                && (path.starts_with(&app_files_folder) || config_files.contains(path))
+        });
+
+        if changed {
+            on_change();
+
+            // the config or a markdown file might point to other files now
+            config_files = files_read_by_config(&yaml_file);
+            if let Err(e) = watch_folders_of(debouncer.watcher(), &config_files, &mut watched) {
+                eprintln!("Error while watching: {}", e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the config and every file it reads, as far as it can be read right now
+fn files_read_by_config(yaml_file: &Path) -> Vec<PathBuf> {
+    let mut files = vec![yaml_file.to_path_buf()];
+
+    if let Ok(config) = AppOutputMdFileConfig::new_from_file(yaml_file) {
+        let config_folder = yaml_file.parent().unwrap_or(Path::new("/"));
+        files.extend(
+            config
+                .tags
+                .vocabulary_file
+                .and_then(|file| canonicalize(&config_folder.join(file)).ok()),
+        );
+        files.extend(config.text_files);
+    }
+
+    files
+}
+
+// start watching the folders of `files` that aren't watched yet
+// folders inside one that's watched recursively already are skipped,
+// watching them again would stop them from being watched recursively
+fn watch_folders_of(
+    watcher: &mut dyn Watcher,
+    files: &[PathBuf],
+    watched: &mut Vec<(PathBuf, RecursiveMode)>,
+) -> Result<(), String> {
+    for folder in files.iter().filter_map(|file| file.parent()) {
+        let is_watched = watched.iter().any(|(watched_folder, mode)| {
+            watched_folder == folder
+                || (*mode == RecursiveMode::Recursive && folder.starts_with(watched_folder))
+        });
+        if is_watched {
+            continue;
+        }
+
+        watcher
+            .watch(folder, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("unable to watch {}: {}", folder.display(), e))?;
+        watched.push((folder.to_path_buf(), RecursiveMode::NonRecursive));
+    }
+
+    Ok(())
+}
+
+fn rebuild(opt: &CommandLineOpts) {
+    println!("\nRebuilding...");
+    match build_md_file(opt) {
+        Ok(_) => println!("Done."),
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+// the other pages next to the output file when the list is split, like `apps-b.md` for `apps.md`
+fn is_split_page(output_md_file: &Path, path: &Path) -> bool {
+    let (Some(stem), Some(name)) = (output_md_file.file_stem(), path.file_name()) else {
+        return false;
+    };
+    let name = name.to_string_lossy();
+
+    path.parent() == output_md_file.parent()
+        && name.starts_with(&format!("{}-", stem.to_string_lossy()))
+        && name.ends_with(".md")
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, String> {
+    path.canonicalize()
+        .map_err(|e| format!("unable to find {}: {}", path.display(), e))
+}
+
+// like `canonicalize`, but the file itself doesn't have to exist yet
+fn absolute_path(path: &Path) -> Result<PathBuf, String> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("{} is not a valid file name", path.display()))?;
+
+    Ok(canonicalize(parent)?.join(file_name))
+}
@@ -0,0 +1,27 @@
+use similar::TextDiff;
+use std::fs::read_to_string;
+use std::path::Path;
+
+// compare what would be written with the file that's there, used by `--check`
+// the file is never touched, a diff is printed if they're different
+pub fn check_output_file(path: &Path, contents: Vec<u8>) -> Result<(), String> {
+    let existing_contents =
+        read_to_string(path).map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+    let contents = String::from_utf8(contents).map_err(|e| e.to_string())?;
+
+    if existing_contents != contents {
+        let file_name = path.display().to_string();
+        print!(
+            "{}",
+            TextDiff::from_lines(&existing_contents, &contents)
+                .unified_diff()
+                .header(&file_name, &file_name)
+        );
+        return Err(format!(
+            "{} is not up to date. Run with --run to regenerate it.",
+            file_name
+        ));
+    }
+
+    Ok(())
+}
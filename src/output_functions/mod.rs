@@ -0,0 +1,50 @@
+pub mod check_output_file;
+pub mod write_output_file;
+
+use deunicode::deunicode;
+use std::path::Path;
+
+// `-` as the output file means stdout instead of a real file
+pub fn is_stdout(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+// for anything put into the html preview
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+// the id zola gives a heading, so the toc can link to it
+// accents and other scripts are turned into plain ascii first, like zola does,
+// then it's lowercase with everything that isn't a letter or a number turned into `-`
+pub fn slugify(text: &str) -> String {
+    deunicode(text)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_makes_the_ids_zola_makes() {
+        assert_eq!(slugify("A"), "a");
+        assert_eq!(slugify("É"), "e");
+        assert_eq!(slugify("Ö"), "o");
+        assert_eq!(slugify("Banking & Finance"), "banking-finance");
+        assert_eq!(slugify("com.example.App"), "com-example-app");
+        assert_eq!(slugify("1"), "1");
+        assert_eq!(slugify("微信"), "wei-xin");
+        assert_eq!(slugify("🚀"), "rocket");
+        assert_eq!(slugify("#"), "");
+        assert_eq!(slugify(""), "");
+    }
+}
@@ -0,0 +1,88 @@
+use crate::output_functions::is_stdout;
+use std::fs::{remove_file, rename, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// write the final file, used by `--run`
+// `render` writes the contents bit by bit, so the whole page never has to be in memory
+// - `-` writes to stdout
+// - if the file already has exactly this content, it isn't touched at all so its
+//   mtime doesn't change and the site doesn't get rebuilt for nothing
+//   this returns whether the file was written
+// - otherwise a temporary file next to the real one is written and then renamed
+//   over it, so a crash halfway through never leaves a truncated page behind
+pub fn write_output_file<F>(path: &Path, render: F) -> Result<bool, String>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    if is_stdout(path) {
+        let mut stdout = BufWriter::new(io::stdout().lock());
+        return render(&mut stdout)
+            .and_then(|_| stdout.flush())
+            .map(|_| true)
+            .map_err(|e| format!("unable to write to stdout: {}", e));
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("{} is not a valid file name", path.display()))?
+        .to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let r = File::create(&temp_path)
+        .and_then(|temp_file| {
+            let mut temp_file = BufWriter::new(temp_file);
+            render(&mut temp_file)?;
+            temp_file
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_all()
+        })
+        .and_then(|_| {
+            if files_have_same_contents(&temp_path, path)? {
+                remove_file(&temp_path).map(|_| false)
+            } else {
+                rename(&temp_path, path).map(|_| true)
+            }
+        });
+
+    r.map_err(|e| {
+        // don't leave the temporary file around if something went wrong
+        let _ = remove_file(&temp_path);
+        format!("unable to write {}: {}", path.display(), e)
+    })
+}
+
+// compare two files chunk by chunk
+// a file that doesn't exist yet is never the same
+fn files_have_same_contents(a: &Path, b: &Path) -> io::Result<bool> {
+    let b = match File::open(b) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let a = File::open(a)?;
+
+    let len = a.metadata()?.len();
+    if len != b.metadata()?.len() {
+        return Ok(false);
+    }
+
+    let mut a = BufReader::new(a);
+    let mut b = BufReader::new(b);
+    let mut a_chunk = [0; 8192];
+    let mut b_chunk = [0; 8192];
+    let mut remaining = len;
+
+    while remaining > 0 {
+        let size = remaining.min(a_chunk.len() as u64) as usize;
+        a.read_exact(&mut a_chunk[..size])?;
+        b.read_exact(&mut b_chunk[..size])?;
+        if a_chunk[..size] != b_chunk[..size] {
+            return Ok(false);
+        }
+        remaining -= size as u64;
+    }
+
+    Ok(true)
+}
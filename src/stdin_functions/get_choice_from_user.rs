@@ -0,0 +1,27 @@
+use std::io;
+
+// get one of a list of choices from stdin, returns the index of the choice
+// used by `AppCompatApp::new_from_command_line()`
+pub fn get_choice_from_user(message: &str, choices: &[String]) -> Result<usize, String> {
+    let stdin = io::stdin();
+    println!("\n{}", message);
+    for (i, choice) in choices.iter().enumerate() {
+        println!("  {}) {}", i + 1, choice);
+    }
+
+    // loop until a valid number is entered
+    loop {
+        let mut input_string = String::new();
+        stdin
+            .read_line(&mut input_string)
+            .map_err(|e| format!("an error occurred while reading input: {}", e))?;
+
+        match input_string.trim().parse::<usize>() {
+            Ok(n) if n >= 1 && n <= choices.len() => return Ok(n - 1),
+            _ => {
+                println!("Invalid entry. Enter a number from 1 to {}.", choices.len());
+                println!("{}", message);
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use crate::stdin_functions::get_option_string_from_user::get_option_string_from_user;
+use crate::structs::app_compat_app::check_date;
+use crate::structs::string_or_none::StringOrNone;
+
+// get an optional `YYYY-MM-DD` date from stdin, used by `AppCompatApp::new_from_command_line()`
+pub fn get_option_date_from_user(message: &str) -> Result<StringOrNone, String> {
+    // loop until the date is valid or left empty
+    loop {
+        let r = get_option_string_from_user(message)?;
+        match &r {
+            StringOrNone(Some(date)) => match check_date(date) {
+                Ok(()) => return Ok(r),
+                Err(e) => println!("Invalid entry. {}", e),
+            },
+            StringOrNone(None) => return Ok(r),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::io;
 
 // get a user input from stdin for strings, used by `AppCompatApp::new_from_command_line()`
-pub fn get_string_from_user(message: &'static str, can_be_empty: bool) -> Result<String, String> {
+pub fn get_string_from_user(message: &str, can_be_empty: bool) -> Result<String, String> {
     let stdin = io::stdin();
     println!("\n{}", message);
     let mut input_string = String::new();
@@ -12,10 +12,10 @@ pub fn get_string_from_user(message: &'static str, can_be_empty: bool) -> Result
     loop {
         stdin
             .read_line(&mut input_string)
-            .map_err(|e| format!("an error occurred while reading input: {}", e.to_string()))?;
+            .map_err(|e| format!("an error occurred while reading input: {}", e))?;
         r = input_string.trim().to_string();
 
-        if !can_be_empty && r.len() == 0 {
+        if !can_be_empty && r.is_empty() {
             println!("Invalid (too short). Try again.");
             println!("{}", message);
         } else {
@@ -1,5 +1,6 @@
-pub mod get_bool_from_user;
+pub mod get_choice_from_user;
 pub mod get_option_bool_from_user;
+pub mod get_option_date_from_user;
 pub mod get_option_string_from_user;
 pub mod get_string_from_user;
 
@@ -21,7 +22,7 @@ pub fn answer_string_to_bool(input: String) -> Result<bool, String> {
 // convert string to actual Optional bool
 pub fn answer_string_to_option_bool(input: String) -> Result<BoolOrNone, String> {
     let trimmed = input.trim();
-    if trimmed.len() == 0 {
+    if trimmed.is_empty() {
         Ok(BoolOrNone(None))
     } else {
         Ok(BoolOrNone(Some(answer_string_to_bool(input)?)))
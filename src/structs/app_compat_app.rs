@@ -1,134 +1,650 @@
+use crate::app_file_functions::{did_you_mean, top_level_key};
+use crate::output_functions::{escape_html, slugify};
 use crate::stdin_functions::get_option_string_from_user::get_option_string_from_user;
 use crate::stdin_functions::{
-    get_bool_from_user::get_bool_from_user, get_option_bool_from_user::get_option_bool_from_user,
+    get_choice_from_user::get_choice_from_user,
+    get_option_bool_from_user::get_option_bool_from_user,
+    get_option_date_from_user::get_option_date_from_user,
     get_string_from_user::get_string_from_user,
 };
+use crate::structs::app_link::{AppLink, LinkKind};
+use crate::structs::app_report::AppReport;
 use crate::structs::bool_or_none::BoolOrNone;
+use crate::structs::compat_status::CompatStatus;
+use crate::structs::report_aggregation::{newest, ReportAggregation};
 use crate::structs::string_or_none::StringOrNone;
+use crate::structs::user_profile::UserProfile;
+use crate::structs::JSON_SCHEMA_DRAFT;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
 use std::fs::File;
-use std::path::PathBuf;
-use url::Url;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use url::{ParseError, Url};
 
-#[derive(Debug, Deserialize, Serialize)]
+// bump this whenever the app file layout changes, and teach `AppCompatAppFile` and
+// `migrate_app_files` about the old layout
+// 1: no `schema_version` yet, `works` and `works_without_compat_mode` or `status`
+// 2: `schema_version` and `status`
+// 3: `category`
+// 4: `tags`
+// 5: `links` instead of `repo_or_download_link`
+// 6: `tested_on`, `os_build`, `device_model`, `user_profile` and `tester`
+// 7: `reports`
+pub const CURRENT_SCHEMA_VERSION: u32 = 7;
+
+// what each field is for, used both when asking for it with `--add` and in the json schema
+pub const APP_NAME_TEXT: &str = "The app's name";
+pub const PACKAGE_NAME_TEXT: &str = "Package name (i.e. com.company.app)";
+pub const VERSION_TEXT: &str = "Version number (i.e. 1.23.4)";
+pub const LINKS_TEXT: &str =
+    "Links to the app's source code, store pages, website or issue tracker";
+pub const DESCRIPTION_TEXT: &str = "App description";
+pub const CATEGORY_TEXT: &str =
+    "Category, like Banking (leave empty to use the name of the folder the file is in)";
+pub const TAGS_TEXT: &str = "Tags, separated by commas (i.e. banking, nfc-payments)";
+pub const STATUS_TEXT: &str = "How well does the app work?";
+pub const WORKS_WITHOUT_GMS_TEXT: &str =
+    "Does the app work without Google Play being installed in the same profile?";
+pub const WORKS_INSTALLED_BY_ANY_SOURCE_TEXT: &str =
+    "Does the app work if installed by an app other than Google Play?";
+pub const OTHER_COMPATIBILITY_COMMENT_TEXT: &str =
+    "Any other comments about the app's compatibility?";
+pub const TESTED_ON_TEXT: &str = "When was it tested? (YYYY-MM-DD, i.e. 2024-05-31)";
+pub const OS_BUILD_TEXT: &str = "OS build number it was tested on (i.e. 2024053100)";
+pub const DEVICE_MODEL_TEXT: &str = "Device model it was tested on (i.e. Pixel 8)";
+pub const USER_PROFILE_TEXT: &str = "Which kind of profile was it tested in?";
+pub const TESTER_TEXT: &str = "Who tested it? (a handle, i.e. @someone)";
+pub const REPORTS_TEXT: &str =
+    "Dated test results, the latest one is shown on the card instead of the fields above";
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(try_from = "AppCompatAppFile")]
 pub struct AppCompatApp {
+    pub schema_version: u32,
     pub app_name: String,
     pub package_name: String,
     pub version: String,
-    pub repo_or_download_link: StringOrNone,
+    pub links: Vec<AppLink>,
     pub description: StringOrNone,
-    pub works: bool,
-    pub works_without_compat_mode: bool,
+    pub category: StringOrNone,
+    pub tags: Vec<String>,
+    pub status: CompatStatus,
     pub works_without_gms: BoolOrNone,
     pub works_installed_by_any_source: BoolOrNone,
     pub other_compatibility_comment: StringOrNone,
+    pub tested_on: StringOrNone,
+    pub os_build: StringOrNone,
+    pub device_model: StringOrNone,
+    pub user_profile: Option<UserProfile>,
+    pub tester: StringOrNone,
+    pub reports: Vec<AppReport>,
+}
+
+// every key `AppCompatAppFile` accepts, `app_file_keys_match_app_file_fields` makes sure
+// this doesn't drift apart from the struct
+const APP_FILE_KEYS: [&str; 21] = [
+    "schema_version",
+    "app_name",
+    "package_name",
+    "version",
+    "repo_or_download_link",
+    "links",
+    "description",
+    "category",
+    "tags",
+    "status",
+    "works",
+    "works_without_compat_mode",
+    "works_without_gms",
+    "works_installed_by_any_source",
+    "other_compatibility_comment",
+    "tested_on",
+    "os_build",
+    "device_model",
+    "user_profile",
+    "tester",
+    "reports",
+];
+
+// what can be in an app file, of any schema version
+// older files are still read, and `--migrate` rewrites them to the current version
+// optional fields can be left out, so adding one doesn't break every existing file
+// anything else is an error though, usually it's a typo
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AppCompatAppFile {
+    schema_version: Option<u32>,
+    app_name: String,
+    package_name: String,
+    version: String,
+    // replaced by `links` in version 5
+    #[serde(default)]
+    repo_or_download_link: StringOrNone,
+    #[serde(default)]
+    links: Vec<AppLink>,
+    #[serde(default)]
+    description: StringOrNone,
+    #[serde(default)]
+    category: StringOrNone,
+    #[serde(default)]
+    tags: Vec<String>,
+    status: Option<CompatStatus>,
+    // replaced by `status` in version 2
+    works: Option<bool>,
+    works_without_compat_mode: Option<bool>,
+    #[serde(default)]
+    works_without_gms: BoolOrNone,
+    #[serde(default)]
+    works_installed_by_any_source: BoolOrNone,
+    #[serde(default)]
+    other_compatibility_comment: StringOrNone,
+    #[serde(default)]
+    tested_on: StringOrNone,
+    #[serde(default)]
+    os_build: StringOrNone,
+    #[serde(default)]
+    device_model: StringOrNone,
+    #[serde(default)]
+    user_profile: Option<UserProfile>,
+    #[serde(default)]
+    tester: StringOrNone,
+    #[serde(default)]
+    reports: Vec<AppReport>,
+}
+
+impl TryFrom<AppCompatAppFile> for AppCompatApp {
+    type Error = String;
+
+    fn try_from(file: AppCompatAppFile) -> Result<Self, Self::Error> {
+        let schema_version = file.schema_version.unwrap_or(1);
+        if schema_version > CURRENT_SCHEMA_VERSION {
+            return Err(format!(
+                "schema_version {} is newer than this program supports ({}), please update it",
+                schema_version, CURRENT_SCHEMA_VERSION
+            ));
+        }
+
+        let status = match (file.status, file.works, file.works_without_compat_mode) {
+            (Some(status), None, None) => status,
+            (None, Some(works), works_without_compat_mode) if schema_version < 2 => {
+                CompatStatus::from_legacy_bools(works, works_without_compat_mode.unwrap_or(false))
+            }
+            (Some(_), _, _) | (None, Some(_), _) => {
+                return Err("`status` replaces `works` and `works_without_compat_mode`, please only use `status`".to_string())
+            }
+            (None, None, _) => return Err("missing field `status`".to_string()),
+        };
+
+        let links = match file.repo_or_download_link {
+            StringOrNone(None) => file.links,
+            StringOrNone(Some(url)) if schema_version < 5 && file.links.is_empty() => {
+                vec![AppLink {
+                    kind: LinkKind::guess_from_url(&url),
+                    url: StringOrNone(Some(url)),
+                }]
+            }
+            StringOrNone(Some(_)) => {
+                return Err(
+                    "`links` replaces `repo_or_download_link`, please only use `links`".to_string(),
+                )
+            }
+        };
+        if let Some(link) = links
+            .iter()
+            .find(|link| link.url(&file.package_name).is_none())
+        {
+            return Err(format!("the `{}` link needs a `url`", link.kind.key()));
+        }
+        if let StringOrNone(Some(date)) = &file.tested_on {
+            check_date(date).map_err(|e| format!("`tested_on`: {}", e))?;
+        }
+        for report in &file.reports {
+            check_date(&report.tested_on).map_err(|e| format!("`tested_on` of a report: {}", e))?;
+        }
+
+        // everything is converted to the current version while reading
+        Ok(Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            app_name: file.app_name,
+            package_name: file.package_name,
+            version: file.version,
+            links,
+            description: file.description,
+            category: file.category,
+            tags: file.tags,
+            status,
+            works_without_gms: file.works_without_gms,
+            works_installed_by_any_source: file.works_installed_by_any_source,
+            other_compatibility_comment: file.other_compatibility_comment,
+            tested_on: file.tested_on,
+            os_build: file.os_build,
+            device_model: file.device_model,
+            user_profile: file.user_profile,
+            tester: file.tester,
+            reports: file.reports,
+        })
+    }
 }
 
 impl AppCompatApp {
     // call this to create a new struct from user input from stdin
     // (this will be used to create the yaml files)
     pub fn new_from_command_line() -> Result<Self, String> {
-        let app_name = get_string_from_user("[required] The app's name:", false)?;
+        let app_name = get_string_from_user(&format!("[required] {}:", APP_NAME_TEXT), false)?;
 
         let package_name =
-            get_string_from_user("[required] Package name (i.e. com.company.app):", false)?;
+            get_string_from_user(&format!("[required] {}:", PACKAGE_NAME_TEXT), false)?;
 
-        let version = get_string_from_user("[required] Version number (i.e. 1.23.4):", false)?;
+        let version = get_string_from_user(&format!("[required] {}:", VERSION_TEXT), false)?;
 
-        let repo_or_download_link =
-            get_option_string_from_user("[optional] Download or repo url:")?;
+        let links = get_links_from_user()?;
 
-        let description = get_option_string_from_user("[optional] App description:")?;
+        let description =
+            get_option_string_from_user(&format!("[optional] {}:", DESCRIPTION_TEXT))?;
 
-        let works = get_bool_from_user("[required][y/n] Does the app work?")?;
+        let category = get_option_string_from_user(&format!("[optional] {}:", CATEGORY_TEXT))?;
 
-        // if works is false, then just return a faster "doesn't work" thing
-        if !works {
+        let tags = get_option_string_from_user(&format!("[optional] {}:", TAGS_TEXT))?
+            .0
+            .map(|tags| split_tags(&tags))
+            .unwrap_or_default();
+
+        let status = CompatStatus::ALL[get_choice_from_user(
+            &format!("[required] {}", STATUS_TEXT),
+            &CompatStatus::ALL.map(|status| status.to_string()),
+        )?];
+
+        let tested_on = get_option_date_from_user(&format!("[optional] {}:", TESTED_ON_TEXT))?;
+        let os_build = get_option_string_from_user(&format!("[optional] {}:", OS_BUILD_TEXT))?;
+        let device_model =
+            get_option_string_from_user(&format!("[optional] {}:", DEVICE_MODEL_TEXT))?;
+        let user_profile = get_user_profile_from_user()?;
+        let tester = get_option_string_from_user(&format!("[optional] {}:", TESTER_TEXT))?;
+
+        // if it doesn't work, then just return a faster "doesn't work" thing
+        if !status.works() {
             return Ok(Self {
+                schema_version: CURRENT_SCHEMA_VERSION,
                 app_name,
                 package_name,
                 version,
-                repo_or_download_link,
+                links,
                 description,
-                works,
-                works_without_compat_mode: false,
+                category,
+                tags,
+                status,
                 works_without_gms: BoolOrNone(None),
                 works_installed_by_any_source: BoolOrNone(None),
                 other_compatibility_comment: StringOrNone(None),
+                tested_on,
+                os_build,
+                device_model,
+                user_profile,
+                tester,
+                reports: vec![],
             });
         }
 
-        // get whether it requires exploit protection compatibility mode
-        let works_without_compat_mode = get_bool_from_user(
-            "[required][y/n] Does the app work without Exploit Protection Compatibility Mode?",
-        )?;
-
         // get whether the app requires Google Play etc to be installed in the same profile
-        let works_without_gms = get_option_bool_from_user("[optional][y/n] Does the app work without Google Play being installed in the same profile?")?;
+        let works_without_gms =
+            get_option_bool_from_user(&format!("[optional][y/n] {}", WORKS_WITHOUT_GMS_TEXT))?;
 
         // get whether the app requires that it's installed by Google Play
-        let works_installed_by_any_source =
-            get_option_bool_from_user("[optional][y/n] Does the app if installed by an app other than Google Play? (y/n or just leave this empty)")?;
+        let works_installed_by_any_source = get_option_bool_from_user(&format!(
+            "[optional][y/n] {} (y/n or just leave this empty)",
+            WORKS_INSTALLED_BY_ANY_SOURCE_TEXT
+        ))?;
 
-        let other_compatibility_comment = get_option_string_from_user(
-            "[optional] Any other comments about the app's compatibility?",
-        )?;
+        let other_compatibility_comment = get_option_string_from_user(&format!(
+            "[optional] {}",
+            OTHER_COMPATIBILITY_COMMENT_TEXT
+        ))?;
 
         Ok(Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             app_name,
             package_name,
             version,
-            repo_or_download_link,
+            links,
             description,
-            works,
-            works_without_compat_mode,
+            category,
+            tags,
+            status,
             works_without_gms,
             works_installed_by_any_source,
             other_compatibility_comment,
+            tested_on,
+            os_build,
+            device_model,
+            user_profile,
+            tester,
+            reports: vec![],
+        })
+    }
+
+    // json schema for app files, used by `--schema app`
+    // it describes the current schema version, so older files will show up as invalid
+    pub fn json_schema() -> JsonValue {
+        json!({
+            "$schema": JSON_SCHEMA_DRAFT,
+            "title": "App file",
+            "description": "One app on the app compatibility page.",
+            "type": "object",
+            "properties": {
+                "schema_version": {
+                    "description": "The version of the app file layout. Run with --migrate to update older files.",
+                    "const": CURRENT_SCHEMA_VERSION,
+                },
+                "app_name": { "description": APP_NAME_TEXT, "type": "string", "minLength": 1 },
+                "package_name": { "description": PACKAGE_NAME_TEXT, "type": "string", "minLength": 1 },
+                "version": { "description": VERSION_TEXT, "type": "string", "minLength": 1 },
+                "links": AppLink::json_schema(LINKS_TEXT),
+                "description": StringOrNone::json_schema(DESCRIPTION_TEXT),
+                "category": StringOrNone::json_schema(CATEGORY_TEXT),
+                "tags": {
+                    "description": "Tags, like banking or nfc-payments",
+                    "type": "array",
+                    "items": { "type": "string", "minLength": 1 },
+                    "uniqueItems": true,
+                },
+                "status": CompatStatus::json_schema(STATUS_TEXT),
+                "works_without_gms": BoolOrNone::json_schema(WORKS_WITHOUT_GMS_TEXT),
+                "works_installed_by_any_source": BoolOrNone::json_schema(WORKS_INSTALLED_BY_ANY_SOURCE_TEXT),
+                "other_compatibility_comment": StringOrNone::json_schema(OTHER_COMPATIBILITY_COMMENT_TEXT),
+                "tested_on": {
+                    "description": format!("{} (null if it's not known)", TESTED_ON_TEXT),
+                    "type": ["string", "null"],
+                    "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}$",
+                },
+                "os_build": StringOrNone::json_schema(OS_BUILD_TEXT),
+                "device_model": StringOrNone::json_schema(DEVICE_MODEL_TEXT),
+                "user_profile": UserProfile::json_schema(USER_PROFILE_TEXT),
+                "tester": StringOrNone::json_schema(TESTER_TEXT),
+                "reports": AppReport::json_schema(REPORTS_TEXT),
+            },
+            "required": ["schema_version", "app_name", "package_name", "version", "status"],
+            "additionalProperties": false,
+        })
+    }
+
+    // parse the contents of one app file, `path` is only used for error messages
+    pub fn new_from_yaml_bytes(bytes: &[u8], path: &Path) -> Result<Self, String> {
+        serde_yaml::from_slice(bytes).map_err(|e| {
+            // serde only names the first unknown key and doesn't say what was meant,
+            // so look for all of them once it's clear there's a problem
+            let unknown_keys = unknown_keys(bytes, path);
+            if unknown_keys.is_empty() {
+                format!("error deserializing {}: {}", path.display(), e)
+            } else {
+                unknown_keys.join("\n")
+            }
         })
     }
 
-    // convenience function to get the first character of the app name
-    pub fn get_name_first_char(&self) -> char {
-        self.app_name
-            .chars()
-            .nth(0)
-            .expect("Unable to get the first letter of an app name. Is it empty?")
-            .to_ascii_uppercase()
+    // the id of the app's card, so it can be linked to directly
+    pub fn anchor(&self) -> String {
+        slugify(&self.package_name)
     }
 
-    pub fn print_card_line(&self) -> String {
-        let general_status_icon = match (self.works, self.works_without_compat_mode) {
-            (true, true) => "✅",
-            (true, false) => "⚠️",
-            _ => "❌",
+    // let the reports drive the card, by copying the one `aggregation` picks over the app's
+    // own result. this is only done to render it, the app file itself keeps its own values
+    pub fn apply_reports(&mut self, aggregation: ReportAggregation) {
+        let Some(report) = aggregation.pick(&self.reports).cloned() else {
+            return;
         };
 
-        let link_host = match &self.repo_or_download_link {
-            StringOrNone(Some(url)) => {
-                let parsed_url = Url::parse(url)
-                    .map_err(|_| format!("error parsing url for {}", &self.repo_or_download_link))
-                    .unwrap();
-                parsed_url.host_str().unwrap().to_string()
+        self.version = report.version;
+        self.status = report.status;
+        self.other_compatibility_comment = report.comment;
+        self.tested_on = StringOrNone(Some(report.tested_on));
+        self.os_build = report.os_build;
+        self.device_model = report.device_model;
+        self.user_profile = report.user_profile;
+        self.tester = report.tester;
+    }
+
+    // whether the reports for the newest version don't agree on the status
+    pub fn conflicting_reports(&self) -> bool {
+        let Some(latest) = newest(self.reports.iter()) else {
+            return false;
+        };
+
+        self.reports
+            .iter()
+            .any(|report| report.version == latest.version && report.status != latest.status)
+    }
+
+    // every report as a list, newest first, empty if there are none
+    // `quote` is used for the attributes, see `AppReport::history_item_html`
+    pub fn history_html(&self, quote: char) -> String {
+        if self.reports.is_empty() {
+            return "".to_string();
+        }
+
+        let mut reports = self.reports.iter().enumerate().collect::<Vec<_>>();
+        reports.sort_by(|(i, a), (j, b)| b.tested_on.cmp(&a.tested_on).then(j.cmp(i)));
+
+        format!(
+            "<ul class={q}history{q}>{}</ul>",
+            reports
+                .iter()
+                .map(|(_, report)| report.history_item_html(quote))
+                .collect::<String>(),
+            q = quote
+        )
+    }
+
+    // the first link, cards that only show one link use this one
+    pub fn main_link(&self) -> StringOrNone {
+        StringOrNone(
+            self.links
+                .first()
+                .and_then(|link| link.url(&self.package_name)),
+        )
+    }
+
+    // make sure every link is a url, so rendering never has to deal with broken ones
+    // links without a scheme, like `github.com/example/app`, are an error unless
+    // `fix_missing_https` is set, then `https://` is added to them
+    pub fn check_links(&mut self, fix_missing_https: bool) -> Result<(), String> {
+        for link in self.links.iter_mut() {
+            let StringOrNone(Some(url)) = &link.url else {
+                continue;
+            };
+
+            match Url::parse(url) {
+                Ok(_) => {}
+                Err(ParseError::RelativeUrlWithoutBase) if fix_missing_https => {
+                    let fixed = format!("https://{}", url);
+                    Url::parse(&fixed).map_err(|e| {
+                        format!("the `{}` link `{}` isn't a valid url: {}", link.kind.key(), url, e)
+                    })?;
+                    link.url = StringOrNone(Some(fixed));
+                }
+                Err(ParseError::RelativeUrlWithoutBase) => {
+                    return Err(format!(
+                        "the `{}` link `{}` doesn't start with a scheme like https://. Add it, or run with --fmt to add it",
+                        link.kind.key(),
+                        url
+                    ))
+                }
+                Err(e) => {
+                    return Err(format!(
+                        "the `{}` link `{}` isn't a valid url: {}",
+                        link.kind.key(),
+                        url,
+                        e
+                    ))
+                }
             }
+        }
+
+        Ok(())
+    }
+
+    // only the host is shown on the card, like `github.com`
+    // links without a host, like `mailto:`, show what comes after the scheme instead
+    // and if it's not a url at all, it's shown as it is
+    pub fn link_host(&self) -> String {
+        match &self.main_link() {
+            StringOrNone(Some(url)) => match Url::parse(url) {
+                Ok(parsed_url) => match parsed_url.host_str() {
+                    Some(host) => host.to_string(),
+                    None => parsed_url.path().to_string(),
+                },
+                Err(_) => url.clone(),
+            },
             StringOrNone(None) => "".to_string(),
-        };
+        }
+    }
 
-        format!("{{{{ app_compat_card( app_name = \"{}\", package_name = \"{}\", version = \"{}\", repo_or_download_link = \"{}\", link_host = \"{}\", description = \"{}\", works = {}, general_status_icon = \"{}\", works_without_gms = \"{}\", works_installed_by_any_source = \"{}\", other_compatibility_comment = \"{}\" ) }}}}",
+    // every link with its icon and label, for the `links_html` card argument
+    // it ends up inside a double quoted argument, so the html only uses single quotes
+    pub fn links_html(&self) -> String {
+        self.links
+            .iter()
+            .filter_map(|link| {
+                let url = link.url(&self.package_name)?;
+                Some(format!(
+                    "<a class='app-link {}' href='{}'>{} {}</a>",
+                    link.kind.key(),
+                    url.replace('\'', "%27"),
+                    link.kind.icon(),
+                    link.kind
+                ))
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn write_card_line(&self, w: &mut dyn Write) -> io::Result<()> {
+        write!(w, "{{{{ app_compat_card( app_name = \"{}\", package_name = \"{}\", version = \"{}\", repo_or_download_link = \"{}\", link_host = \"{}\", description = \"{}\", works = {}, general_status_icon = \"{}\", status = \"{}\", status_text = \"{}\", works_without_gms = \"{}\", works_installed_by_any_source = \"{}\", other_compatibility_comment = \"{}\", anchor = \"{}\", links_html = \"{}\", tested_on = \"{}\", os_build = \"{}\", device_model = \"{}\", user_profile = \"{}\", tester = \"{}\", history_html = \"{}\", conflicting_reports = {} ) }}}}",
             self.app_name,
             self.package_name,
             self.version,
-            self.repo_or_download_link,
-            link_host,
+            self.main_link(),
+            self.link_host(),
             self.description,
-            self.works,
-            general_status_icon,
+            self.status.works(),
+            self.status.icon(),
+            self.status.key(),
+            self.status,
             self.works_without_gms,
             self.works_installed_by_any_source,
             self.other_compatibility_comment,
+            self.anchor(),
+            self.links_html(),
+            self.tested_on,
+            self.os_build,
+            self.device_model,
+            self.user_profile
+                .map(|profile| profile.to_string())
+                .unwrap_or_default(),
+            self.tester,
+            self.history_html('\''),
+            self.conflicting_reports(),
         )
     }
 
+    // when, where and by whom it was tested, in one line
+    // like `2024-05-31 · Pixel 8 · build 2024053100 · Owner · by @someone`
+    // `None` if none of it is known
+    pub fn tested_text(&self) -> Option<String> {
+        let parts = [
+            self.tested_on.0.clone(),
+            self.device_model.0.clone(),
+            self.os_build
+                .0
+                .as_ref()
+                .map(|build| format!("build {}", build)),
+            self.user_profile.map(|profile| profile.to_string()),
+            self.tester
+                .0
+                .as_ref()
+                .map(|tester| format!("by {}", tester)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>();
+
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    // the same card as `write_card_line`, but as plain html for the preview server
+    // `description` and `other_compatibility_comment` already are html at this point
+    // (see `replace_new_lines_with_p_tags`), so they're not escaped
+    pub fn write_card_html(&self, w: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            w,
+            "<div class=\"app-compat-card\" id=\"{}\">",
+            escape_html(&self.anchor())
+        )?;
+        writeln!(
+            w,
+            "<h3><span class=\"status\" title=\"{}\">{}</span> {}</h3>",
+            self.status,
+            self.status.icon(),
+            escape_html(&self.app_name)
+        )?;
+        writeln!(w, "<p class=\"status-text\">{}</p>", self.status)?;
+        if self.conflicting_reports() {
+            writeln!(
+                w,
+                "<p class=\"conflicting-reports\">⚠️ Conflicting reports, see the history</p>"
+            )?;
+        }
+        writeln!(
+            w,
+            "<p class=\"package-name\"><code>{}</code> version {}</p>",
+            escape_html(&self.package_name),
+            escape_html(&self.version)
+        )?;
+        let links = self
+            .links
+            .iter()
+            .filter_map(|link| {
+                let url = link.url(&self.package_name)?;
+                Some(format!(
+                    "<a class=\"app-link {}\" href=\"{}\">{} {}</a>",
+                    link.kind.key(),
+                    escape_html(&url),
+                    link.kind.icon(),
+                    link.kind
+                ))
+            })
+            .collect::<Vec<String>>();
+        if !links.is_empty() {
+            writeln!(w, "<p class=\"links\">{}</p>", links.join(" · "))?;
+        }
+        if let StringOrNone(Some(description)) = &self.description {
+            writeln!(w, "<div class=\"description\">{}</div>", description)?;
+        }
+        writeln!(
+            w,
+            "<ul>\n<li>Works without Google Play: {}</li>\n<li>Works if installed by any source: {}</li>\n</ul>",
+            self.works_without_gms, self.works_installed_by_any_source
+        )?;
+        if let StringOrNone(Some(comment)) = &self.other_compatibility_comment {
+            writeln!(w, "<div class=\"comment\">{}</div>", comment)?;
+        }
+        if let Some(tested) = self.tested_text() {
+            writeln!(
+                w,
+                "<p class=\"tested\">Tested: {}</p>",
+                escape_html(&tested)
+            )?;
+        }
+        if !self.reports.is_empty() {
+            writeln!(
+                w,
+                "<details>\n<summary>History</summary>\n{}\n</details>",
+                self.history_html('"')
+            )?;
+        }
+        writeln!(w, "</div>")
+    }
+
     pub fn save_to_file(&self, path: &mut PathBuf) -> Result<(), String> {
         path.push(format!("{}.yaml", &self.package_name));
 
@@ -137,12 +653,39 @@ impl AppCompatApp {
             return Err("This file already exists.\nPlease update the file manually.".to_string());
         }
 
+        self.write_to_file(path)
+    }
+
+    // write the app to a yaml file, replacing whatever is there
+    pub fn write_to_file(&self, path: &Path) -> Result<(), String> {
         let new_config_file = File::create(path).map_err(|e| e.to_string())?;
         serde_yaml::to_writer(&new_config_file, self).map_err(|e| e.to_string())?;
 
         Ok(())
     }
 
+    // get rid of whitespace that doesn't mean anything, used by `--fmt`
+    pub fn trim_all_string_fields(&mut self) {
+        self.app_name = self.app_name.trim().to_string();
+        self.package_name = self.package_name.trim().to_string();
+        self.version = self.version.trim().to_string();
+
+        self.description = self.description.trimmed();
+        self.category = self.category.trimmed();
+        self.tags = self.tags.iter().map(|tag| tag.trim().to_string()).collect();
+        for link in self.links.iter_mut() {
+            link.url = link.url.trimmed();
+        }
+        self.other_compatibility_comment = self.other_compatibility_comment.trimmed();
+        self.tested_on = self.tested_on.trimmed();
+        self.os_build = self.os_build.trimmed();
+        self.device_model = self.device_model.trimmed();
+        self.tester = self.tester.trimmed();
+        for report in self.reports.iter_mut() {
+            report.trim_all_string_fields();
+        }
+    }
+
     // remove all double quotes here so that they don't mess up the
     // shortcode in the `.md` file
     // This shouldn't be necessary, but I'll use as a failsafe.
@@ -152,12 +695,20 @@ impl AppCompatApp {
         self.version = self.version.replace("\"", "'");
 
         self.description = self.description.replace_double_quotes_with_single_quotes();
-        self.repo_or_download_link = self
-            .repo_or_download_link
-            .replace_double_quotes_with_single_quotes();
+        self.category = self.category.replace_double_quotes_with_single_quotes();
+        self.tags = self.tags.iter().map(|tag| tag.replace("\"", "'")).collect();
+        for link in self.links.iter_mut() {
+            link.url = link.url.replace_double_quotes_with_single_quotes();
+        }
         self.other_compatibility_comment = self
             .other_compatibility_comment
             .replace_double_quotes_with_single_quotes();
+        self.os_build = self.os_build.replace_double_quotes_with_single_quotes();
+        self.device_model = self.device_model.replace_double_quotes_with_single_quotes();
+        self.tester = self.tester.replace_double_quotes_with_single_quotes();
+        for report in self.reports.iter_mut() {
+            report.replace_double_quotes_from_all_string_fields();
+        }
     }
 
     // replace all \n in comments with <br>
@@ -167,5 +718,274 @@ impl AppCompatApp {
         self.other_compatibility_comment = self
             .other_compatibility_comment
             .replace_new_lines_with_p_tags();
+        for report in self.reports.iter_mut() {
+            report.comment = report.comment.replace_new_lines_with_p_tags();
+        }
+    }
+}
+
+// every top-level key that isn't one of `APP_FILE_KEYS`, with a suggestion for what was meant
+// the file is read as yaml, so quoted keys and flow mappings work too
+// the line is only added when the key can be found at the start of one
+fn unknown_keys(bytes: &[u8], path: &Path) -> Vec<String> {
+    let Ok(serde_yaml::Value::Mapping(mapping)) = serde_yaml::from_slice(bytes) else {
+        return vec![];
+    };
+    let text = String::from_utf8_lossy(bytes);
+
+    mapping
+        .keys()
+        .filter_map(serde_yaml::Value::as_str)
+        .filter(|key| !APP_FILE_KEYS.contains(key))
+        .map(|key| {
+            let line = text
+                .lines()
+                .position(|line| top_level_key(line) == Some(key));
+            format!(
+                "{}{}: unknown field `{}`{}",
+                path.display(),
+                line.map(|i| format!(":{}", i + 1)).unwrap_or_default(),
+                key,
+                did_you_mean(key, &APP_FILE_KEYS)
+            )
+        })
+        .collect()
+}
+
+// dates are always written like `2024-05-31`, so comparing them as text sorts them too
+pub fn check_date(date: &str) -> Result<(), String> {
+    let error = || format!("`{}` isn't a date like 2024-05-31", date);
+
+    let parts = date.split('-').collect::<Vec<&str>>();
+    let [year, month, day] = parts[..] else {
+        return Err(error());
+    };
+    if year.len() != 4
+        || month.len() != 2
+        || day.len() != 2
+        || !date.chars().all(|c| c.is_ascii_digit() || c == '-')
+    {
+        return Err(error());
+    }
+
+    let year = year.parse::<u32>().map_err(|_| error())?;
+    let month = month.parse::<u32>().map_err(|_| error())?;
+    let day = day.parse::<u32>().map_err(|_| error())?;
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap_year => 29,
+        2 => 28,
+        _ => return Err(error()),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(error());
+    }
+
+    Ok(())
+}
+
+// `banking, nfc-payments` -> [`banking`, `nfc-payments`]
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+// the profile is optional, so there's an extra choice for not knowing it
+pub fn get_user_profile_from_user() -> Result<Option<UserProfile>, String> {
+    let choices = UserProfile::ALL
+        .iter()
+        .map(|profile| profile.to_string())
+        .chain(std::iter::once("Don't know".to_string()))
+        .collect::<Vec<String>>();
+    let choice = get_choice_from_user(&format!("[optional] {}", USER_PROFILE_TEXT), &choices)?;
+
+    Ok(UserProfile::ALL.get(choice).copied())
+}
+
+// ask for every kind of link
+// store pages are made from the package name, so those only need a yes
+fn get_links_from_user() -> Result<Vec<AppLink>, String> {
+    let mut links = vec![];
+
+    for kind in LinkKind::ALL {
+        if kind.url_from_package_name("").is_some() {
+            let answer =
+                get_option_bool_from_user(&format!("[optional][y/n] Is the app on {}?", kind))?;
+            if answer.0 == Some(true) {
+                links.push(AppLink {
+                    kind,
+                    url: StringOrNone(None),
+                });
+            }
+        } else {
+            let url = get_option_string_from_user(&format!("[optional] {} url:", kind))?;
+            if url.0.is_some() {
+                links.push(AppLink { kind, url });
+            }
+        }
+    }
+
+    Ok(links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // serde lists every field it accepts when it sees an unknown one,
+    // like "unknown field `x`, expected one of `a`, `b` at line 1 column 1"
+    fn app_file_fields() -> Vec<String> {
+        let e = serde_yaml::from_str::<AppCompatAppFile>("not_a_field: 1")
+            .err()
+            .unwrap()
+            .to_string();
+        let expected = e.split("expected one of ").nth(1).unwrap();
+
+        expected
+            .split(", ")
+            .map(|field| field.split('`').nth(1).unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn app_file_keys_match_app_file_fields() {
+        let mut fields = app_file_fields();
+        fields.sort();
+        let mut keys = APP_FILE_KEYS.map(String::from).to_vec();
+        keys.sort();
+
+        assert_eq!(fields, keys);
+    }
+
+    #[test]
+    fn quoted_keys_are_valid() {
+        let app = AppCompatApp::new_from_yaml_bytes(
+            b"\"app_name\": beta\n'package_name': com.beta\nversion: '1'\nstatus: works\n",
+            Path::new("beta.yaml"),
+        )
+        .unwrap();
+
+        assert_eq!(app.app_name, "beta");
+        assert_eq!(app.package_name, "com.beta");
+    }
+
+    #[test]
+    fn flow_mappings_are_valid() {
+        let app = AppCompatApp::new_from_yaml_bytes(
+            b"{ app_name: beta, package_name: com.beta, version: '1', status: works }\n",
+            Path::new("beta.yaml"),
+        )
+        .unwrap();
+
+        assert_eq!(app.app_name, "beta");
+    }
+
+    #[test]
+    fn unknown_keys_are_all_reported_with_a_suggestion() {
+        let e = AppCompatApp::new_from_yaml_bytes(
+            b"app_name: beta\npackage_name: com.beta\nversion: '1'\nstatsu: works\n\"tester_\": me\n",
+            Path::new("beta.yaml"),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            e,
+            "beta.yaml:4: unknown field `statsu`, did you mean `status`?\nbeta.yaml:5: unknown field `tester_`, did you mean `tester`?"
+        );
+    }
+
+    // an app with one report per `(tested_on, version, status)`
+    fn app_with_reports(reports: &[(&str, &str, &str)]) -> AppCompatApp {
+        let reports = reports
+            .iter()
+            .map(|(tested_on, version, status)| {
+                format!(
+                    "- tested_on: {}\n  version: '{}'\n  status: {}\n",
+                    tested_on, version, status
+                )
+            })
+            .collect::<String>();
+
+        AppCompatApp::new_from_yaml_bytes(
+            format!(
+                "app_name: beta\npackage_name: com.beta\nversion: '1'\nstatus: works\nreports:\n{}",
+                reports
+            )
+            .as_bytes(),
+            Path::new("beta.yaml"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_only_conflict_for_the_newest_version() {
+        assert!(!app_with_reports(&[]).conflicting_reports());
+        assert!(!app_with_reports(&[("2024-01-01", "2", "works")]).conflicting_reports());
+        assert!(app_with_reports(&[
+            ("2024-01-01", "2", "works"),
+            ("2024-01-02", "2", "does_not_work"),
+        ])
+        .conflicting_reports());
+        assert!(!app_with_reports(&[
+            ("2024-01-01", "1", "does_not_work"),
+            ("2024-01-02", "2", "works"),
+            ("2024-01-03", "2", "works"),
+        ])
+        .conflicting_reports());
+    }
+
+    #[test]
+    fn reports_from_the_same_day_count_in_file_order() {
+        // the newest report is the last one of the day, the other one still disagrees with it
+        assert!(app_with_reports(&[
+            ("2024-01-01", "2", "does_not_work"),
+            ("2024-01-01", "2", "works"),
+        ])
+        .conflicting_reports());
+        // the same day, but the newest version is the one further down in the file
+        assert!(!app_with_reports(&[
+            ("2024-01-01", "2", "does_not_work"),
+            ("2024-01-01", "3", "works"),
+        ])
+        .conflicting_reports());
+    }
+
+    #[test]
+    fn check_date_knows_leap_years_and_month_lengths() {
+        for date in ["2024-02-29", "2000-02-29", "2023-12-31", "2024-04-30"] {
+            assert_eq!(check_date(date), Ok(()), "{}", date);
+        }
+        for date in [
+            "2023-02-29",
+            "1900-02-29",
+            "2024-04-31",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-01-00",
+        ] {
+            assert!(check_date(date).is_err(), "{}", date);
+        }
+    }
+
+    #[test]
+    fn check_date_only_takes_yyyy_mm_dd() {
+        for date in [
+            "2024-5-1",
+            "24-05-01",
+            "2024/05/01",
+            "2024-05-01-01",
+            "+024-05-01",
+            "",
+            "２０２４-０５-０１",
+        ] {
+            assert_eq!(
+                check_date(date),
+                Err(format!("`{}` isn't a date like 2024-05-31", date))
+            );
+        }
     }
 }
@@ -1,129 +1,732 @@
+use crate::app_file_functions::{did_you_mean, list_app_files};
+use crate::output_functions::{escape_html, slugify};
 use crate::structs::app_compat_app::AppCompatApp;
-use std::fs::{read_dir, File};
-use std::path::PathBuf;
+use crate::structs::app_file_scan_options::AppFileScanOptions;
+use crate::structs::app_filter::AppFilter;
+use crate::structs::build_cache::{hash_bytes, BuildCache, CachedApp};
+use crate::structs::group_by::GroupBy;
+use crate::structs::report_aggregation::ReportAggregation;
+use crate::structs::sort_options::SortOptions;
+use crate::structs::split_options::{SplitBy, SplitOptions};
+use crate::structs::tag_options::TagOptions;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::read;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 pub struct AppCompatList {
-    apps: Vec<AppCompatApp>,
-    toc: Vec<String>,
+    apps: Vec<LoadedApp>,
+    // every group once, in the order they're printed
+    toc: Vec<Group>,
 }
 
-const FAKE_FIRST_CHAR: char = 'z';
+// one of the pages the list is split into
+pub struct ListPage {
+    // added to the file name of every page but the first, like `b` for `apps-b.md`
+    pub name: String,
+    // what links to it are called, like `B` or `A–C`
+    pub label: String,
+    // the apps on it
+    apps: Range<usize>,
+}
 
-const DIV_START_STRING: &'static str =
-    "{{ raw_html( html = \"<div class='app-compat-list-section'>\") }}";
-const DIV_END_STRING: &'static str = "{{ raw_html( html = \"</div>\") }}";
+// one section of the list, like `A` or `Banking`
+struct Group {
+    heading: String,
+    // the heading's id, unique on the page
+    anchor: String,
+}
 
-const TOC_CLASS: &'static str = "toc";
+// an app plus what's needed to cache it
+struct LoadedApp {
+    app: AppCompatApp,
+    // path relative to the app files folder
+    file: String,
+    hash: String,
+    // already rendered card, only when the cache is used
+    card: Option<String>,
+    // the app's own category, or the folder its file is in
+    category: Option<String>,
+    // index of its group in `toc`, set by `sort_list`
+    group: usize,
+}
 
-impl AppCompatList {
-    pub fn new_from_folder(folder: PathBuf) -> Result<Self, String> {
-        let dir = read_dir(folder).map_err(|e| format!("unable to read the folder: {}", e))?;
-
-        let mut toc = vec![];
-
-        // iterate through the directory scan results
-        // filter out filenames
-        // grab data from yaml files
-        let list = dir
-            .filter_map(|file| {
-                let f = file.expect("error getting file from directory list").path();
-
-                let filename = f
-                    .file_name()
-                    .expect("cannot read file's file name")
-                    .to_str()
-                    .expect("cannot convert filename to string");
-
-                // skip the template (any file with a filename that starts with `_`,
-                // directories, any dot files, and any files that aren't yaml files
-                if f.is_dir()
-                    || !(filename.ends_with(".yaml") || filename.ends_with(".yml"))
-                    || filename.starts_with(".")
-                    || filename.starts_with("_")
-                {
-                    println!("Skipping {}", filename);
-                    return None;
-                }
+const DIV_START_STRING: &str = "{{ raw_html( html = \"<div class='app-compat-list-section'>\") }}";
+const DIV_END_STRING: &str = "{{ raw_html( html = \"</div>\") }}";
+
+const TOC_CLASS: &str = "toc";
+
+// the section for apps without a category, always the last one
+const UNCATEGORIZED: &str = "Other";
+
+// the id of headings that have no letters or numbers to make one from, like `#`
+const SYMBOLS_ANCHOR: &str = "symbols";
+
+// the heading above the tag sections, after every group
+const TAGS_HEADING: &str = "Tags";
 
-                let file = File::open(&f).expect("error opening file");
+impl AppCompatList {
+    // `fix_missing_https` is passed on to `AppCompatApp::check_links`,
+    // `aggregation` to `AppCompatApp::apply_reports`
+    pub fn new_from_folder(
+        folder: PathBuf,
+        scan: &AppFileScanOptions,
+        fix_missing_https: bool,
+        aggregation: ReportAggregation,
+        cache: &BuildCache,
+    ) -> Result<Self, String> {
+        let files = list_app_files(&folder, scan)?;
 
-                let mut app: AppCompatApp = serde_yaml::from_reader(file)
-                    .expect("there was an error deserializing the file, so panicking");
+        // grab data from yaml files, spread across all cores
+        // `collect` keeps the results in the same order as `files`
+        let results = files
+            .par_iter()
+            .map(|f| {
+                let bytes = read(f).map_err(|e| format!("error opening {}: {}", f.display(), e))?;
+                let hash = hash_bytes(&bytes);
+                let file = f
+                    .strip_prefix(&folder)
+                    .unwrap_or(f)
+                    .to_string_lossy()
+                    .to_string();
 
-                let letter: String = app.get_name_first_char().to_lowercase().to_string();
-                if !toc.contains(&letter) {
-                    toc.push(letter);
+                // unchanged files don't need to be parsed or rendered again
+                if let Some(cached) = cache.get_app(&file, &hash) {
+                    return Ok(LoadedApp {
+                        category: category_of(&cached.app, &file),
+                        app: cached.app.clone(),
+                        file,
+                        hash,
+                        card: Some(cached.card.clone()),
+                        group: 0,
+                    });
                 }
 
+                let mut app = AppCompatApp::new_from_yaml_bytes(&bytes, f)?;
+                app.check_links(fix_missing_https)
+                    .map_err(|e| format!("{}: {}", f.display(), e))?;
+
+                app.apply_reports(aggregation);
                 app.replace_double_quotes_from_all_string_fields();
                 app.replace_new_lines_with_p_tags();
 
-                Some(app)
+                // render the card now while we're spread across all cores anyway,
+                // but only if it'll be cached, otherwise it's written straight to the output
+                let card = if cache.is_enabled() {
+                    let mut card = vec![];
+                    app.write_card_line(&mut card).map_err(|e| e.to_string())?;
+                    Some(String::from_utf8(card).map_err(|e| e.to_string())?)
+                } else {
+                    None
+                };
+
+                Ok(LoadedApp {
+                    category: category_of(&app, &file),
+                    app,
+                    file,
+                    hash,
+                    card,
+                    group: 0,
+                })
+            })
+            .collect::<Vec<Result<LoadedApp, String>>>();
+
+        // report every broken file at once instead of only the first one
+        let mut list = vec![];
+        let mut errors = vec![];
+        for r in results {
+            match r {
+                Ok(app) => list.push(app),
+                Err(e) => errors.push(e),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        Ok(Self {
+            apps: list,
+            toc: vec![],
+        })
+    }
+
+    // leave out the apps the filter doesn't match
+    // call this after `update_cache`, so the cache still knows the other apps
+    // for the next time the filter changes
+    pub fn filter(&mut self, filter: &AppFilter) {
+        self.apps.retain(|loaded| filter.matches(&loaded.app));
+    }
+
+    // sort the apps into their groups, the toc is only there after this
+    pub fn sort_list(&mut self, group_by: GroupBy, sort: &SortOptions) -> Result<(), String> {
+        let collator = sort.collator()?;
+        let letters = sort.letter_collator()?;
+
+        let mut apps = std::mem::take(&mut self.apps)
+            .into_iter()
+            .map(|loaded| {
+                let heading = match group_by {
+                    GroupBy::Letter => sort.letter_of(&loaded.app.app_name, &letters),
+                    GroupBy::Category => loaded
+                        .category
+                        .clone()
+                        .unwrap_or_else(|| UNCATEGORIZED.to_string()),
+                };
+                (heading, loaded)
             })
-            .collect::<Vec<AppCompatApp>>();
+            .collect::<Vec<(String, LoadedApp)>>();
+
+        // anything without a category goes last
+        let goes_last = |heading: &str| group_by == GroupBy::Category && heading == UNCATEGORIZED;
+        apps.sort_by(|(a_heading, a), (b_heading, b)| {
+            goes_last(a_heading)
+                .cmp(&goes_last(b_heading))
+                .then_with(|| collator.compare(a_heading, b_heading))
+                .then_with(|| collator.compare(&a.app.app_name, &b.app.app_name))
+        });
+
+        self.toc = vec![];
+        for (heading, mut loaded) in apps {
+            if self.toc.last().map(|group| &group.heading) != Some(&heading) {
+                let anchor = self.unique_anchor(&heading);
+                self.toc.push(Group { heading, anchor });
+            }
+            loaded.group = self.toc.len() - 1;
+            self.apps.push(loaded);
+        }
 
-        Ok(Self { apps: list, toc })
+        Ok(())
     }
 
-    pub fn sort_list(&mut self) {
+    // every card's anchor has to be unique, and can't be the same as a heading's either
+    // that includes the tags heading, if the tag sections are shown
+    pub fn check_anchors(&self, tags: &TagOptions) -> Result<(), String> {
+        let mut used: HashMap<String, &str> = HashMap::new();
+        let mut errors = vec![];
+        let tags_anchor = (tags.sections && !self.tags().is_empty()).then(|| self.tags_anchor());
+
+        for loaded in self.apps.iter() {
+            let anchor = loaded.app.anchor();
+            if anchor.is_empty() {
+                errors.push(format!(
+                    "{}: can't make an anchor from the package name `{}`",
+                    loaded.file, loaded.app.package_name
+                ));
+            } else if let Some(group) = self.toc.iter().find(|group| group.anchor == anchor) {
+                errors.push(format!(
+                    "{}: the anchor `{}` is already used by the `{}` heading",
+                    loaded.file, anchor, group.heading
+                ));
+            } else if tags_anchor.as_ref() == Some(&anchor) {
+                errors.push(format!(
+                    "{}: the anchor `{}` is already used by the `{}` heading",
+                    loaded.file, anchor, TAGS_HEADING
+                ));
+            } else if let Some(other) = used.get(&anchor) {
+                errors.push(format!(
+                    "{}: the anchor `{}` is already used by {}",
+                    loaded.file, anchor, other
+                ));
+            } else {
+                used.insert(anchor, &loaded.file);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        Ok(())
+    }
+
+    // the same id zola would make for the heading,
+    // with `-1`, `-2`... added if another group already has it
+    fn unique_anchor(&self, heading: &str) -> String {
+        let mut anchor = slugify(heading);
+        if anchor.is_empty() {
+            anchor = SYMBOLS_ANCHOR.to_string();
+        }
+
+        let mut unique = anchor.clone();
+        let mut i = 1;
+        while self.toc.iter().any(|group| group.anchor == unique) {
+            unique = format!("{}-{}", anchor, i);
+            i += 1;
+        }
+
+        unique
+    }
+
+    // the id of the tags heading, it comes after the groups so it's the one that gets a number
+    fn tags_anchor(&self) -> String {
+        self.unique_anchor(TAGS_HEADING)
+    }
+
+    // every app with the category the list uses for it
+    pub fn apps(&self) -> impl Iterator<Item = (&AppCompatApp, Option<&str>)> {
         self.apps
-            .sort_by(|a, b| a.app_name.to_lowercase().cmp(&b.app_name.to_lowercase()));
-        self.toc.sort();
+            .iter()
+            .map(|loaded| (&loaded.app, loaded.category.as_deref()))
+    }
+
+    // with a tag vocabulary, every tag has to be in it
+    pub fn check_tags(&self, tags: &TagOptions) -> Result<(), String> {
+        let Some(vocabulary) = &tags.vocabulary else {
+            return Ok(());
+        };
+        let known_tags = vocabulary.keys().map(String::as_str).collect::<Vec<&str>>();
+
+        let errors = self
+            .apps
+            .iter()
+            .flat_map(|loaded| {
+                loaded
+                    .app
+                    .tags
+                    .iter()
+                    .filter(|tag| !vocabulary.contains_key(*tag))
+                    .map(|tag| {
+                        format!(
+                            "{}: unknown tag `{}`{}",
+                            loaded.file,
+                            tag,
+                            did_you_mean(tag, &known_tags)
+                        )
+                    })
+            })
+            .collect::<Vec<String>>();
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        Ok(())
     }
 
-    pub fn print_cards_list(&self) -> String {
-        // this will kind of be like the table of contents
-        let mut contents_list = vec![];
+    // every tag any app has, sorted
+    pub fn tags(&self) -> Vec<String> {
+        self.apps
+            .iter()
+            .flat_map(|loaded| loaded.app.tags.iter().cloned())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    // the cache only keeps the apps that are in the folder right now
+    pub fn update_cache(&self, cache: &mut BuildCache) {
+        if !cache.is_enabled() {
+            return;
+        }
+
+        cache.set_apps(
+            self.apps
+                .iter()
+                .filter_map(|loaded| {
+                    let card = loaded.card.clone()?;
+                    Some((
+                        loaded.file.clone(),
+                        CachedApp {
+                            hash: loaded.hash.clone(),
+                            app: loaded.app.clone(),
+                            card,
+                        },
+                    ))
+                })
+                .collect(),
+        );
+    }
+
+    // split the sorted list into pages, whole groups stay together with `SplitBy::Group`
+    pub fn split(&self, split: &SplitOptions) -> Vec<ListPage> {
+        // there's always at least one page, even if it's empty
+        if self.apps.is_empty() {
+            return vec![ListPage {
+                name: String::new(),
+                label: String::new(),
+                apps: 0..0,
+            }];
+        }
+
+        let ranges: Vec<Range<usize>> = match split.by {
+            SplitBy::None => std::iter::once(0..self.apps.len()).collect(),
+            SplitBy::Group => {
+                let mut ranges: Vec<Range<usize>> = vec![];
+                for (i, loaded) in self.apps.iter().enumerate() {
+                    match ranges.last_mut() {
+                        Some(range) if self.apps[range.start].group == loaded.group => {
+                            range.end = i + 1
+                        }
+                        _ => ranges.push(i..i + 1),
+                    }
+                }
+                ranges
+            }
+            SplitBy::Count => (0..self.apps.len())
+                .step_by(split.apps_per_page.max(1))
+                .map(|start| start..(start + split.apps_per_page.max(1)).min(self.apps.len()))
+                .collect(),
+        };
+
+        ranges
+            .into_iter()
+            .enumerate()
+            .map(|(i, apps)| {
+                let first = &self.toc[self.apps[apps.start].group];
+                let last = &self.toc[self.apps[apps.end - 1].group];
+                ListPage {
+                    name: match split.by {
+                        SplitBy::Group => first.anchor.clone(),
+                        _ => (i + 1).to_string(),
+                    },
+                    label: if first.heading == last.heading {
+                        first.heading.clone()
+                    } else {
+                        format!("{}–{}", first.heading, last.heading)
+                    },
+                    apps,
+                }
+            })
+            .collect()
+    }
+
+    pub fn write_cards_list(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.write_cards(&self.apps, w)
+    }
+
+    // only the cards on one of the pages from `split`
+    pub fn write_page_cards_list(&self, page: &ListPage, w: &mut dyn Write) -> io::Result<()> {
+        self.write_cards(&self.apps[page.apps.clone()], w)
+    }
 
-        // all strings to print later
-        // includes things like `# A` and apps
-        let mut strings_list = vec![];
+    fn write_cards(&self, apps: &[LoadedApp], w: &mut dyn Write) -> io::Result<()> {
+        // things like `# A`, the `<div>`s and apps are written as soon as they're ready
+        // with line breaks between them, so nothing before the first one
+        let mut separator = "";
 
         // the list should already be sorted, so just assuming that to make it easier
-        // 'Z' won't be the first, so just using it as a placeholder
-        let mut last: char = FAKE_FIRST_CHAR;
+        let mut last: Option<usize> = None;
 
         // todo make the <div></div> for groups suck way less
-        for app in self.apps.iter() {
-            // build the "table of contents" / `contents_list`
-            // and at the same time, this first part will open or close `<div>`
-            let letter = app.get_name_first_char();
-            if letter != last {
-                if letter != FAKE_FIRST_CHAR {
-                    // end the previous div
-                    strings_list.push(DIV_END_STRING.to_string());
-                }
-                contents_list.push(letter);
-                strings_list.push(format!("\n# {}\n", letter));
-                last = letter;
+        for LoadedApp {
+            app, card, group, ..
+        } in apps.iter()
+        {
+            // this first part will open or close `<div>` when the group changes
+            if last != Some(*group) {
+                // end the previous div
+                // this is also written before the first group, which pages have always had
+                write!(w, "{}{}", separator, DIV_END_STRING)?;
+                separator = "\n";
+
+                write!(w, "{}\n# {}\n", separator, md_heading(&self.toc[*group]))?;
+                last = Some(*group);
 
                 // new div should start after the new letter is added
-                strings_list.push(DIV_START_STRING.to_string());
+                write!(w, "\n{}", DIV_START_STRING)?;
             }
 
             // finally add the app entry
-            strings_list.push(app.print_card_line());
+            writeln!(w)?;
+            match card {
+                Some(card) => write!(w, "{}", card)?,
+                None => app.write_card_line(w)?,
+            }
         }
 
         // should close the last <div>
-        strings_list.push(DIV_END_STRING.to_string());
+        write!(w, "{}{}", separator, DIV_END_STRING)
+    }
+
+    // the same groups as `write_cards_list`, but with a link to every app's own page
+    // from `--output-dir` instead of its card
+    pub fn write_md_page_links(&self, w: &mut dyn Write) -> io::Result<()> {
+        let mut last: Option<usize> = None;
+
+        for LoadedApp { app, group, .. } in self.apps.iter() {
+            if last != Some(*group) {
+                writeln!(w, "\n# {}\n", md_heading(&self.toc[*group]))?;
+                last = Some(*group);
+            }
+
+            writeln!(
+                w,
+                "- {} [{}]({}/)",
+                app.status.icon(),
+                app.app_name,
+                app.anchor()
+            )?;
+        }
 
-        // print the strings list with line breaks between
-        strings_list.join("\n")
+        Ok(())
     }
 
-    pub fn print_md_toc(&self) -> String {
-        self.toc
+    pub fn write_md_toc(&self, w: &mut dyn Write) -> io::Result<()> {
+        for (i, group) in self.toc.iter().enumerate() {
+            if i > 0 {
+                write!(w, " | ")?;
+            }
+            write!(w, "[{}](#{})", group.heading, group.anchor)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_md_toc_wrapped_in_div(&self, w: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            w,
+            "{{{{ raw_html( html = \"<div class='{}'>\") }}}}",
+            TOC_CLASS
+        )?;
+        self.write_md_toc(w)?;
+        write!(w, "\n{{{{ raw_html( html = \"</div>\") }}}}")
+    }
+
+    // the same toc as `write_md_toc_wrapped_in_div`, but every group links to the page
+    // from `split` it starts on, `page_url` turns a page into the link to it
+    pub fn write_md_pages_toc_wrapped_in_div(
+        &self,
+        pages: &[ListPage],
+        page_url: &dyn Fn(&ListPage) -> String,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(
+            w,
+            "{{{{ raw_html( html = \"<div class='{}'>\") }}}}",
+            TOC_CLASS
+        )?;
+        for (i, group) in self.toc.iter().enumerate() {
+            if i > 0 {
+                write!(w, " | ")?;
+            }
+            let page = pages
+                .iter()
+                .find(|page| self.apps[page.apps.clone()].iter().any(|a| a.group == i));
+            match page {
+                Some(page) => write!(
+                    w,
+                    "[{}]({}#{})",
+                    group.heading,
+                    page_url(page),
+                    group.anchor
+                )?,
+                None => write!(w, "{}", group.heading)?,
+            }
+        }
+        write!(w, "\n{{{{ raw_html( html = \"</div>\") }}}}")
+    }
+
+    pub fn write_html_toc(&self, w: &mut dyn Write) -> io::Result<()> {
+        write!(w, "<nav class=\"{}\">", TOC_CLASS)?;
+        for (i, group) in self.toc.iter().enumerate() {
+            if i > 0 {
+                write!(w, " | ")?;
+            }
+            write!(
+                w,
+                "<a href=\"#{}\">{}</a>",
+                escape_html(&group.anchor),
+                escape_html(&group.heading)
+            )?;
+        }
+        writeln!(w, "</nav>")
+    }
+
+    // the same list as `write_cards_list`, but as plain html for the preview server
+    pub fn write_html_cards_list(&self, w: &mut dyn Write) -> io::Result<()> {
+        let mut last: Option<usize> = None;
+
+        for LoadedApp { app, group, .. } in self.apps.iter() {
+            if last != Some(*group) {
+                if last.is_some() {
+                    writeln!(w, "</section>")?;
+                }
+                let Group { heading, anchor } = &self.toc[*group];
+                writeln!(
+                    w,
+                    "<section class=\"app-compat-list-section\" id=\"{}\">\n<h2>{}</h2>",
+                    escape_html(anchor),
+                    escape_html(heading)
+                )?;
+                last = Some(*group);
+            }
+
+            app.write_card_html(w)?;
+        }
+
+        if last.is_some() {
+            writeln!(w, "</section>")?;
+        }
+
+        Ok(())
+    }
+
+    // package name -> anchor of its card, for linking to apps from elsewhere
+    pub fn write_permalink_index(&self, w: &mut dyn Write) -> io::Result<()> {
+        let index = self
+            .apps
             .iter()
-            .map(|letter| format!("[{}](#{})", letter.to_uppercase(), letter))
-            .collect::<Vec<String>>()
-            .join(" | ")
+            .map(|loaded| (loaded.app.package_name.as_str(), loaded.app.anchor()))
+            .collect::<BTreeMap<&str, String>>();
+
+        serde_json::to_writer_pretty(&mut *w, &index)?;
+        writeln!(w)
     }
 
-    pub fn print_md_toc_wrapped_in_div(&self) -> String {
-        format!("{{{{ raw_html( html = \"<div class='{}'>\") }}}}\n{}\n{{{{ raw_html( html = \"</div>\") }}}}",
-                TOC_CLASS,
-            self.print_md_toc()
-        )
+    // a section for every tag with the apps that have it, after the list
+    // nothing at all if no app has a tag
+    pub fn write_md_tag_sections(&self, tags: &TagOptions, w: &mut dyn Write) -> io::Result<()> {
+        let all_tags = self.tags();
+        if all_tags.is_empty() {
+            return Ok(());
+        }
+
+        let heading = Group {
+            heading: TAGS_HEADING.to_string(),
+            anchor: self.tags_anchor(),
+        };
+        write!(w, "\n\n# {}", md_heading(&heading))?;
+        for tag in all_tags {
+            write!(w, "\n\n## {}\n", tags.title(&tag))?;
+            for loaded in self.apps.iter().filter(|a| a.app.tags.contains(&tag)) {
+                write!(w, "\n- {}", loaded.app.app_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    // the same as `write_md_tag_sections`, but as plain html for the preview server
+    pub fn write_html_tag_sections(&self, tags: &TagOptions, w: &mut dyn Write) -> io::Result<()> {
+        let all_tags = self.tags();
+        if all_tags.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            w,
+            "<section class=\"tags\" id=\"{}\">\n<h2>{}</h2>",
+            self.tags_anchor(),
+            TAGS_HEADING
+        )?;
+        for tag in all_tags {
+            writeln!(w, "<h3>{}</h3>\n<ul>", escape_html(tags.title(&tag)))?;
+            for loaded in self.apps.iter().filter(|a| a.app.tags.contains(&tag)) {
+                writeln!(w, "<li>{}</li>", escape_html(&loaded.app.app_name))?;
+            }
+            writeln!(w, "</ul>")?;
+        }
+        writeln!(w, "</section>")
+    }
+}
+
+// `# {heading}` for the list
+// the id is only spelled out when zola wouldn't make the same one by itself
+fn md_heading(group: &Group) -> String {
+    // a heading that's only `#` would be an empty heading otherwise
+    let heading = match group.heading.starts_with('#') {
+        true => format!("\\{}", group.heading),
+        false => group.heading.clone(),
+    };
+
+    if slugify(&group.heading) == group.anchor {
+        heading
+    } else {
+        format!("{} {{#{}}}", heading, group.anchor)
+    }
+}
+
+// an explicit `category` wins, otherwise it's the first folder the file is in
+// files right in the app files folder don't have one
+fn category_of(app: &AppCompatApp, file: &str) -> Option<String> {
+    app.category.0.clone().or_else(|| {
+        Path::new(file)
+            .parent()?
+            .components()
+            .next()
+            .map(|folder| folder.as_os_str().to_string_lossy().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // an empty list with these groups
+    fn list_with_groups(headings: &[&str]) -> AppCompatList {
+        let mut list = AppCompatList {
+            apps: vec![],
+            toc: vec![],
+        };
+        for heading in headings {
+            let anchor = list.unique_anchor(heading);
+            list.toc.push(Group {
+                heading: heading.to_string(),
+                anchor,
+            });
+        }
+
+        list
+    }
+
+    fn anchors(headings: &[&str]) -> Vec<String> {
+        list_with_groups(headings)
+            .toc
+            .into_iter()
+            .map(|group| group.anchor)
+            .collect()
+    }
+
+    #[test]
+    fn unique_anchor_numbers_duplicate_slugs() {
+        assert_eq!(
+            anchors(&["A", "E", "É", "O", "Ö", "Z"]),
+            ["a", "e", "e-1", "o", "o-1", "z"]
+        );
+        assert_eq!(
+            anchors(&["#", "🚀", "微信"]),
+            ["symbols", "rocket", "wei-xin"]
+        );
+        assert_eq!(
+            anchors(&["#", "Symbols", "?"]),
+            ["symbols", "symbols-1", "symbols-2"]
+        );
+    }
+
+    #[test]
+    fn headings_spell_out_ids_zola_would_not_make() {
+        let heading = |heading: &str, anchor: &str| {
+            md_heading(&Group {
+                heading: heading.to_string(),
+                anchor: anchor.to_string(),
+            })
+        };
+
+        assert_eq!(heading("É", "e"), "É");
+        assert_eq!(heading("Ö", "o-1"), "Ö {#o-1}");
+        assert_eq!(heading("#", "symbols"), "\\# {#symbols}");
+    }
+
+    #[test]
+    fn the_tags_heading_gets_its_own_anchor() {
+        assert_eq!(list_with_groups(&["Banking"]).tags_anchor(), "tags");
+        assert_eq!(
+            list_with_groups(&["Banking", "Tags"]).tags_anchor(),
+            "tags-1"
+        );
+    }
+
+    #[test]
+    fn tag_sections_are_left_out_without_tags() {
+        let list = list_with_groups(&["A"]);
+        let mut md = vec![];
+        list.write_md_tag_sections(&TagOptions::default(), &mut md)
+            .unwrap();
+        let mut html = vec![];
+        list.write_html_tag_sections(&TagOptions::default(), &mut html)
+            .unwrap();
+
+        assert!(md.is_empty());
+        assert!(html.is_empty());
+        assert_eq!(list.check_anchors(&TagOptions::default()), Ok(()));
     }
 }
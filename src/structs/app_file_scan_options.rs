@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// which files in the app files folder are app files
+// the defaults are what used to be hardcoded: `.yaml` and `.yml` files, but not
+// dot files or templates (anything starting with `_`), and no subfolders
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppFileScanOptions {
+    // glob patterns, like `*.yaml`
+    // patterns with a `/` are matched against the path relative to the app files folder,
+    // all others only against the file or folder name
+    pub include: Vec<String>,
+    // same as `include`, but matching folders are skipped as a whole
+    pub exclude: Vec<String>,
+    // look in subfolders too
+    pub recursive: bool,
+    // comes from `--quiet`, not the config
+    #[serde(skip)]
+    pub quiet: bool,
+}
+
+impl Default for AppFileScanOptions {
+    fn default() -> Self {
+        Self {
+            include: vec!["*.yaml".to_string(), "*.yml".to_string()],
+            exclude: vec![".*".to_string(), "_*".to_string()],
+            recursive: false,
+            quiet: false,
+        }
+    }
+}
+
+impl AppFileScanOptions {
+    pub fn json_schema() -> Value {
+        let defaults = Self::default();
+
+        json!({
+            "description": "Which files in the app files folder are app files",
+            "type": "object",
+            "properties": {
+                "include": {
+                    "description": "Glob patterns for app files. Patterns with a / match the path relative to the app files folder, all others only the file name.",
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": defaults.include,
+                },
+                "exclude": {
+                    "description": "Glob patterns for files and folders to skip, matched like include",
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": defaults.exclude,
+                },
+                "recursive": {
+                    "description": "Look for app files in subfolders too",
+                    "type": "boolean",
+                    "default": defaults.recursive,
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use crate::structs::app_compat_app::{check_date, AppCompatApp};
+use crate::structs::string_or_none::StringOrNone;
+use crate::structs::user_profile::UserProfile;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// which apps end up in the output, by when, where and by whom they were tested
+// everything that's left empty doesn't filter anything, and apps that don't say
+// what a filter looks at are left out as soon as that filter is used
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppFilter {
+    // only apps tested on or after this date, like `2024-05-31`
+    pub tested_since: Option<String>,
+    // only apps tested on or before this date
+    pub tested_until: Option<String>,
+    // only apps tested on one of these
+    pub os_builds: Vec<String>,
+    pub device_models: Vec<String>,
+    pub user_profiles: Vec<UserProfile>,
+    // only apps tested by one of these
+    pub testers: Vec<String>,
+}
+
+impl AppFilter {
+    pub fn json_schema() -> Value {
+        let date = |description: &str| {
+            json!({
+                "description": description,
+                "type": ["string", "null"],
+                "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}$",
+            })
+        };
+        let list = |description: &str| {
+            json!({
+                "description": description,
+                "type": "array",
+                "items": { "type": "string" },
+            })
+        };
+
+        json!({
+            "description": "Only list apps tested at a certain time, on certain devices or by certain testers. Empty filters don't filter anything, apps that don't say what a filter looks at are left out.",
+            "type": "object",
+            "properties": {
+                "tested_since": date("Only apps tested on or after this date (YYYY-MM-DD)"),
+                "tested_until": date("Only apps tested on or before this date (YYYY-MM-DD)"),
+                "os_builds": list("Only apps tested on one of these OS build numbers"),
+                "device_models": list("Only apps tested on one of these device models"),
+                "user_profiles": {
+                    "description": "Only apps tested in one of these kinds of profile",
+                    "type": "array",
+                    "items": { "enum": UserProfile::ALL.map(|profile| profile.key()) },
+                },
+                "testers": list("Only apps tested by one of these testers"),
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    // catch a bad date right away instead of silently filtering everything out
+    pub fn check(&self) -> Result<(), String> {
+        for (key, date) in [
+            ("tested_since", &self.tested_since),
+            ("tested_until", &self.tested_until),
+        ] {
+            if let Some(date) = date {
+                check_date(date).map_err(|e| format!("filter.{}: {}", key, e))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, app: &AppCompatApp) -> bool {
+        let tested_on = app.tested_on.0.as_deref();
+
+        self.tested_since
+            .as_deref()
+            .is_none_or(|since| tested_on.is_some_and(|date| date >= since))
+            && self
+                .tested_until
+                .as_deref()
+                .is_none_or(|until| tested_on.is_some_and(|date| date <= until))
+            && one_of(&self.os_builds, &app.os_build)
+            && one_of(&self.device_models, &app.device_model)
+            && (self.user_profiles.is_empty()
+                || app
+                    .user_profile
+                    .is_some_and(|profile| self.user_profiles.contains(&profile)))
+            && one_of(&self.testers, &app.tester)
+    }
+}
+
+// an empty list lets everything through
+fn one_of(allowed: &[String], value: &StringOrNone) -> bool {
+    allowed.is_empty()
+        || value
+            .0
+            .as_ref()
+            .is_some_and(|value| allowed.contains(value))
+}
@@ -0,0 +1,177 @@
+use crate::structs::string_or_none::StringOrNone;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+use url::Url;
+
+// what a link on a card points to, each gets its own icon
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Source,
+    Fdroid,
+    Play,
+    Website,
+    Issue,
+}
+
+impl LinkKind {
+    pub const ALL: [LinkKind; 5] = [
+        LinkKind::Source,
+        LinkKind::Fdroid,
+        LinkKind::Play,
+        LinkKind::Website,
+        LinkKind::Issue,
+    ];
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            LinkKind::Source => "💻",
+            LinkKind::Fdroid => "📦",
+            LinkKind::Play => "▶️",
+            LinkKind::Website => "🌐",
+            LinkKind::Issue => "🐞",
+        }
+    }
+
+    // the same name that's used in the yaml files
+    pub fn key(&self) -> &'static str {
+        match self {
+            LinkKind::Source => "source",
+            LinkKind::Fdroid => "fdroid",
+            LinkKind::Play => "play",
+            LinkKind::Website => "website",
+            LinkKind::Issue => "issue",
+        }
+    }
+
+    // store links can be made from the package name, so they don't need a url
+    pub fn url_from_package_name(&self, package_name: &str) -> Option<String> {
+        match self {
+            LinkKind::Fdroid => Some(format!("https://f-droid.org/packages/{}/", package_name)),
+            LinkKind::Play => Some(format!(
+                "https://play.google.com/store/apps/details?id={}",
+                package_name
+            )),
+            _ => None,
+        }
+    }
+
+    // what the old `repo_or_download_link` most likely was
+    // plenty of them don't have a scheme, like `github.com/x/x`, those are read as `https://`
+    pub fn guess_from_url(url: &str) -> Self {
+        let host = Url::parse(url)
+            .or_else(|_| Url::parse(&format!("https://{}", url)))
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_string()))
+            .unwrap_or_default();
+
+        match host.trim_start_matches("www.") {
+            "f-droid.org" => LinkKind::Fdroid,
+            "play.google.com" => LinkKind::Play,
+            "github.com" | "gitlab.com" | "codeberg.org" | "git.sr.ht" => LinkKind::Source,
+            _ => LinkKind::Website,
+        }
+    }
+}
+
+impl Display for LinkKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let r = match self {
+            LinkKind::Source => "Source code",
+            LinkKind::Fdroid => "F-Droid",
+            LinkKind::Play => "Google Play",
+            LinkKind::Website => "Website",
+            LinkKind::Issue => "Issue tracker",
+        };
+        write!(f, "{}", r)
+    }
+}
+
+// one link on a card
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AppLink {
+    pub kind: LinkKind,
+    // can be left out for `fdroid` and `play`, see `LinkKind::url_from_package_name`
+    #[serde(default, skip_serializing_if = "StringOrNone::is_none")]
+    pub url: StringOrNone,
+}
+
+impl AppLink {
+    pub fn json_schema(description: &str) -> Value {
+        json!({
+            "description": description,
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "kind": {
+                        "description": "What the link points to",
+                        "oneOf": LinkKind::ALL
+                            .iter()
+                            .map(|kind| json!({
+                                "const": kind.key(),
+                                "description": format!("{} {}", kind.icon(), kind),
+                            }))
+                            .collect::<Vec<Value>>(),
+                    },
+                    "url": {
+                        "description": "The link. Can be left out for fdroid and play, then it's made from the package name.",
+                        "type": ["string", "null"],
+                    },
+                },
+                "required": ["kind"],
+                "additionalProperties": false,
+            },
+        })
+    }
+
+    // the link's own url, or the one made from the package name
+    pub fn url(&self, package_name: &str) -> Option<String> {
+        self.url
+            .0
+            .clone()
+            .or_else(|| self.kind.url_from_package_name(package_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guess_from_url_looks_at_the_host() {
+        assert_eq!(
+            LinkKind::guess_from_url("https://github.com/x/x"),
+            LinkKind::Source
+        );
+        assert_eq!(
+            LinkKind::guess_from_url("https://www.f-droid.org/packages/com.x/"),
+            LinkKind::Fdroid
+        );
+        assert_eq!(
+            LinkKind::guess_from_url("https://play.google.com/store/apps/details?id=com.x"),
+            LinkKind::Play
+        );
+        assert_eq!(
+            LinkKind::guess_from_url("https://example.com"),
+            LinkKind::Website
+        );
+    }
+
+    #[test]
+    fn guess_from_url_without_a_scheme() {
+        assert_eq!(LinkKind::guess_from_url("github.com/x/x"), LinkKind::Source);
+        assert_eq!(
+            LinkKind::guess_from_url("www.codeberg.org/x/x"),
+            LinkKind::Source
+        );
+        assert_eq!(
+            LinkKind::guess_from_url("play.google.com/store/apps/details?id=com.x"),
+            LinkKind::Play
+        );
+        assert_eq!(LinkKind::guess_from_url("example.com"), LinkKind::Website);
+        assert_eq!(LinkKind::guess_from_url(""), LinkKind::Website);
+    }
+}
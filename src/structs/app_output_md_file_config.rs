@@ -1,10 +1,442 @@
+use crate::output_functions::escape_html;
+use crate::structs::app_compat_list::{AppCompatList, ListPage};
+use crate::structs::app_file_scan_options::AppFileScanOptions;
+use crate::structs::app_filter::AppFilter;
+use crate::structs::app_page_options::{AppPageOptions, GENERATED_PAGE_MARKER};
+use crate::structs::group_by::GroupBy;
+use crate::structs::report_aggregation::ReportAggregation;
+use crate::structs::sort_options::SortOptions;
+use crate::structs::split_options::SplitOptions;
+use crate::structs::string_or_none::StringOrNone;
+use crate::structs::tag_options::TagOptions;
+use crate::structs::JSON_SCHEMA_DRAFT;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::fs::{read_to_string, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
+// a line in a markdown file (or in `before_text`/`after_text`) that looks like this
+// gets replaced with the contents of the file it points to
+// e.g. `<!-- include: snippets/compat_mode.md -->`
+const INCLUDE_DIRECTIVE_START: &str = "<!-- include:";
+const INCLUDE_DIRECTIVE_END: &str = "-->";
+
+// the previous and next links when the list is split
+const PAGE_NAV_CLASS: &str = "page-nav";
+
+// just enough to make the html preview readable
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 60em; margin: auto; padding: 1em; } \
+.md-text { white-space: pre-wrap; color: #555; } \
+.toc { margin: 1em 0; } \
+.app-compat-card { border: 1px solid #ccc; border-radius: 0.5em; padding: 0.5em 1em; margin: 1em 0; }";
+
+// unknown keys are an error, like `additionalProperties: false` in the json schema
 #[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct AppOutputMdFileConfig {
     pub title: String,
     pub description: String,
     pub alias: String,
     pub before_text: Option<String>,
     pub after_text: Option<String>,
+    // markdown files used instead of `before_text` and `after_text`
+    // paths are relative to the config file
+    pub before_text_file: Option<PathBuf>,
+    pub after_text_file: Option<PathBuf>,
+    // which files in the app files folder are app files
+    #[serde(default)]
+    pub app_files: AppFileScanOptions,
+    // which apps end up in the output
+    #[serde(default)]
+    pub filter: AppFilter,
+    // how the cards are split into sections
+    #[serde(default)]
+    pub group_by: GroupBy,
+    // sorting and which letter apps are listed under
+    #[serde(default)]
+    pub sorting: SortOptions,
+    // the tag vocabulary, taxonomy and sections
+    #[serde(default)]
+    pub tags: TagOptions,
+    // the page every app gets with `--output-dir`
+    #[serde(default)]
+    pub app_pages: AppPageOptions,
+    // spread the list over several pages
+    #[serde(default)]
+    pub split: SplitOptions,
+    // which report a card shows when an app has several
+    #[serde(default)]
+    pub report_aggregation: ReportAggregation,
+    // add `https://` to links that don't have a scheme instead of failing
+    #[serde(default)]
+    pub fix_missing_https: bool,
+    // every markdown file the before and after text were read from, includes too
+    // `--watch` rebuilds when one of them changes
+    #[serde(skip)]
+    pub text_files: Vec<PathBuf>,
+}
+
+impl AppOutputMdFileConfig {
+    // json schema for the config file, used by `--schema config`
+    pub fn json_schema() -> JsonValue {
+        json!({
+            "$schema": JSON_SCHEMA_DRAFT,
+            "title": "Output config",
+            "description": "Settings for the generated app compatibility page.",
+            "type": "object",
+            "properties": {
+                "title": { "description": "The page's title", "type": "string" },
+                "description": { "description": "The page's description", "type": "string" },
+                "alias": { "description": "Another url for the page", "type": "string" },
+                "before_text": StringOrNone::json_schema("Markdown shown before the list, can't be used together with before_text_file"),
+                "after_text": StringOrNone::json_schema("Markdown shown after the list, can't be used together with after_text_file"),
+                "before_text_file": StringOrNone::json_schema("Markdown file (relative to this config) shown before the list"),
+                "after_text_file": StringOrNone::json_schema("Markdown file (relative to this config) shown after the list"),
+                "app_files": AppFileScanOptions::json_schema(),
+                "filter": AppFilter::json_schema(),
+                "group_by": GroupBy::json_schema(),
+                "sorting": SortOptions::json_schema(),
+                "tags": TagOptions::json_schema(),
+                "app_pages": AppPageOptions::json_schema(),
+                "split": SplitOptions::json_schema(),
+                "report_aggregation": ReportAggregation::json_schema(),
+                "fix_missing_https": {
+                    "description": "Add https:// to links in app files that don't start with a scheme, instead of failing",
+                    "type": "boolean",
+                    "default": false,
+                },
+            },
+            "required": ["title", "description", "alias"],
+            "additionalProperties": false,
+        })
+    }
+
+    // read the config file and load `before_text` and `after_text`,
+    // either from the config itself or from the markdown files it points to
+    // include directives are resolved here too, so the rest of the program
+    // only ever has to deal with the final text
+    pub fn new_from_file(path: &Path) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|e| format!("unable to open {}: {}", path.display(), e))?;
+        let mut config: Self = serde_yaml::from_reader(file)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+
+        let config_folder = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let mut text_files = vec![];
+        config.before_text = load_text(
+            "before",
+            config.before_text.take(),
+            &config.before_text_file,
+            &config_folder,
+            &mut text_files,
+        )?;
+        config.after_text = load_text(
+            "after",
+            config.after_text.take(),
+            &config.after_text_file,
+            &config_folder,
+            &mut text_files,
+        )?;
+        text_files.sort();
+        text_files.dedup();
+        config.text_files = text_files;
+        config.tags.load_vocabulary(&config_folder)?;
+        config.app_pages.load_template(&config_folder)?;
+        // catch a bad locale or filter right away
+        config.sorting.collator()?;
+        config.filter.check()?;
+
+        Ok(config)
+    }
+
+    // write the whole `.md` file: front matter, before text, toc, cards and after text
+    // the before or after section is left empty if the config doesn't have one
+    pub fn write_md_file(&self, apps: &AppCompatList, w: &mut dyn Write) -> io::Result<()> {
+        self.write_front_matter(apps, &self.title, true, w)?;
+        writeln!(w, "{}", self.before_text.as_deref().unwrap_or_default())?;
+        apps.write_md_toc_wrapped_in_div(w)?;
+        writeln!(w)?;
+        apps.write_cards_list(w)?;
+        if self.tags.sections {
+            apps.write_md_tag_sections(&self.tags, w)?;
+        }
+        write!(w, "\n\n{}", self.after_text.as_deref().unwrap_or_default())
+    }
+
+    // the index page for `--output-dir`: the same text as the `.md` file,
+    // but with links to every app's page instead of the cards
+    // it's a zola section, so it doesn't take the alias (that's the `.md` file's) or taxonomies
+    pub fn write_index_page(&self, apps: &AppCompatList, w: &mut dyn Write) -> io::Result<()> {
+        self.write_front_matter(apps, &self.title, false, w)?;
+        writeln!(w, "{}", self.before_text.as_deref().unwrap_or_default())?;
+        apps.write_md_toc_wrapped_in_div(w)?;
+        writeln!(w)?;
+        apps.write_md_page_links(w)?;
+        write!(w, "\n\n{}", self.after_text.as_deref().unwrap_or_default())
+    }
+
+    // one of the pages from `AppCompatList::split`, used instead of `write_md_file` when splitting
+    // the first page is the main one with the alias and before text, the last one gets the
+    // after text, and every page links to the ones before and after it
+    // `page_url` turns a page into the link to it
+    pub fn write_md_page(
+        &self,
+        apps: &AppCompatList,
+        pages: &[ListPage],
+        i: usize,
+        page_url: &dyn Fn(&ListPage) -> String,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let first = i == 0;
+        let last = i + 1 == pages.len();
+
+        let title = match first {
+            true => self.title.clone(),
+            false => format!("{}: {}", self.title, pages[i].label),
+        };
+        self.write_front_matter(apps, &title, first, w)?;
+        match first {
+            true => writeln!(w, "{}", self.before_text.as_deref().unwrap_or_default())?,
+            // the other pages are removed again if they aren't needed anymore
+            false => writeln!(w, "{}", GENERATED_PAGE_MARKER)?,
+        }
+
+        apps.write_md_pages_toc_wrapped_in_div(pages, page_url, w)?;
+        writeln!(w)?;
+        apps.write_page_cards_list(&pages[i], w)?;
+
+        write!(
+            w,
+            "\n\n{{{{ raw_html( html = \"<div class='{}'>\") }}}}\n",
+            PAGE_NAV_CLASS
+        )?;
+        if !first {
+            let previous = &pages[i - 1];
+            write!(w, "[← {}]({})", previous.label, page_url(previous))?;
+        }
+        if !first && !last {
+            write!(w, " | ")?;
+        }
+        if !last {
+            let next = &pages[i + 1];
+            write!(w, "[{} →]({})", next.label, page_url(next))?;
+        }
+        write!(w, "\n{{{{ raw_html( html = \"</div>\") }}}}")?;
+
+        if last {
+            if self.tags.sections {
+                apps.write_md_tag_sections(&self.tags, w)?;
+            }
+            write!(w, "\n\n{}", self.after_text.as_deref().unwrap_or_default())?;
+        }
+        Ok(())
+    }
+
+    // only the main page gets the alias and the taxonomy,
+    // so neither of them point to several pages
+    // the other pages (and the `--output-dir` index) only get the title and description
+    fn write_front_matter(
+        &self,
+        apps: &AppCompatList,
+        title: &str,
+        main_page: bool,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        write!(
+            w,
+            "+++\ntitle = \"{}\"\ndescription = \"{}\"\n\n",
+            title, self.description
+        )?;
+        if main_page {
+            write!(w, "aliases = [\"{}\"]\n\n", self.alias)?;
+            if let Some(taxonomy) = &self.tags.taxonomy {
+                writeln!(
+                    w,
+                    "[taxonomies]\n{} = [{}]\n",
+                    taxonomy,
+                    apps.tags()
+                        .iter()
+                        .map(|tag| format!("\"{}\"", tag))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )?;
+            }
+        }
+        write!(w, "[extra]\n\nrelated = []\n+++\n")
+    }
+
+    // a standalone html page with the same contents as the `.md` file, used by the preview server
+    // the before and after text are shown as they are, there's no markdown renderer here
+    // `body_end` is added right before `</body>`, like a script
+    pub fn write_html_file(
+        &self,
+        apps: &AppCompatList,
+        body_end: &str,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(
+            w,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<meta name=\"description\" content=\"{}\">\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>",
+            escape_html(&self.title),
+            escape_html(&self.description),
+            HTML_STYLE,
+            escape_html(&self.title),
+        )?;
+        if let Some(before_text) = &self.before_text {
+            writeln!(
+                w,
+                "<div class=\"md-text\">{}</div>",
+                escape_html(before_text)
+            )?;
+        }
+        apps.write_html_toc(w)?;
+        apps.write_html_cards_list(w)?;
+        if self.tags.sections {
+            apps.write_html_tag_sections(&self.tags, w)?;
+        }
+        if let Some(after_text) = &self.after_text {
+            writeln!(
+                w,
+                "<div class=\"md-text\">{}</div>",
+                escape_html(after_text)
+            )?;
+        }
+        writeln!(w, "{}\n</body>\n</html>", body_end)
+    }
+}
+
+// get the text either from the config or from the file, but not both
+// every markdown file that's read is added to `files`
+fn load_text(
+    section: &str,
+    text: Option<String>,
+    text_file: &Option<PathBuf>,
+    config_folder: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<Option<String>, String> {
+    match (text, text_file) {
+        (Some(_), Some(_)) => Err(format!(
+            "both {}_text and {}_text_file are set in the config. Please only use one.",
+            section, section
+        )),
+        (Some(text), None) => Ok(Some(resolve_includes(
+            &text,
+            config_folder,
+            &mut vec![],
+            files,
+        )?)),
+        (None, Some(text_file)) => Ok(Some(read_md_file(
+            &config_folder.join(text_file),
+            &mut vec![],
+            files,
+        )?)),
+        (None, None) => Ok(None),
+    }
+}
+
+// read a markdown file and resolve its includes relative to its own folder
+// `stack` holds the files currently being read so that a file including itself
+// (directly or through other files) is reported instead of looping forever
+fn read_md_file(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<String, String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("unable to find markdown file {}: {}", path.display(), e))?;
+
+    if stack.contains(&canonical) {
+        return Err(format!(
+            "{} includes itself. Include chain: {}",
+            path.display(),
+            stack
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        ));
+    }
+
+    let text = read_to_string(&canonical)
+        .map_err(|e| format!("unable to read markdown file {}: {}", path.display(), e))?;
+
+    files.push(canonical.clone());
+    stack.push(canonical.clone());
+    let folder = canonical.parent().unwrap_or(Path::new("")).to_path_buf();
+    let r = resolve_includes(&text, &folder, stack, files);
+    stack.pop();
+
+    // don't leave the file's final new line, the md file template adds its own
+    Ok(r?.trim_end_matches('\n').to_string())
+}
+
+fn resolve_includes(
+    text: &str,
+    folder: &Path,
+    stack: &mut Vec<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<String, String> {
+    text.split('\n')
+        .map(|line| match parse_include_directive(line) {
+            Some(include_path) => read_md_file(&folder.join(include_path), stack, files),
+            None => Ok(line.to_string()),
+        })
+        .collect::<Result<Vec<String>, String>>()
+        .map(|lines| lines.join("\n"))
+}
+
+// returns the path if the whole line is an include directive
+fn parse_include_directive(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix(INCLUDE_DIRECTIVE_START)?
+        .strip_suffix(INCLUDE_DIRECTIVE_END)
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_keys_are_an_error() {
+        let e = serde_yaml::from_str::<AppOutputMdFileConfig>(
+            "title: t\ndescription: d\nalias: /a\ngroup-by: category\n",
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(e.starts_with("unknown field `group-by`"), "{}", e);
+    }
+
+    #[test]
+    fn json_schema_has_every_key() {
+        // serde lists every field it accepts when it sees an unknown one
+        let e = serde_yaml::from_str::<AppOutputMdFileConfig>("not_a_field: 1")
+            .unwrap_err()
+            .to_string();
+        let mut fields = e
+            .split("expected one of ")
+            .nth(1)
+            .unwrap()
+            .split(", ")
+            .map(|field| field.split('`').nth(1).unwrap().to_string())
+            .collect::<Vec<String>>();
+        fields.sort();
+
+        let schema = AppOutputMdFileConfig::json_schema();
+        let mut keys = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<String>>();
+        keys.sort();
+
+        assert_eq!(fields, keys);
+    }
 }
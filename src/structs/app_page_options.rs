@@ -0,0 +1,122 @@
+use crate::structs::app_compat_app::AppCompatApp;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs::read_to_string;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// every generated page has this line right after its front matter,
+// so pages of apps that were deleted can be removed without touching anything else
+pub const GENERATED_PAGE_MARKER: &str =
+    "<!-- generated by create_md_pages, changes will be overwritten -->";
+
+// used when there's no `template_file`
+const DEFAULT_TEMPLATE: &str = "+++
+title = \"{app_name}\"
+description = \"{status_icon} {status_text}\"
+
+[extra]
+package_name = \"{package_name}\"
++++";
+
+// the page every app gets with `--output-dir`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppPageOptions {
+    // front matter for every app's page, relative to the config
+    // `{app_name}`, `{package_name}`, `{version}`, `{status}`, `{status_text}`,
+    // `{status_icon}`, `{category}`, `{anchor}`, `{tested_on}`, `{os_build}`, `{device_model}`,
+    // `{user_profile}` and `{tester}` are replaced with the app's values
+    // `{tags}` becomes the app's tags quoted and separated by commas, like `"banking", "nfc"`,
+    // so `tags = [{tags}]` puts the page in the taxonomy
+    pub template_file: Option<PathBuf>,
+    // loaded from `template_file`
+    #[serde(skip)]
+    pub template: Option<String>,
+}
+
+impl AppPageOptions {
+    pub fn json_schema() -> Value {
+        json!({
+            "description": "The page every app gets with --output-dir",
+            "type": "object",
+            "properties": {
+                "template_file": {
+                    "description": "Front matter for every app's page (relative to this config). {app_name}, {package_name}, {version}, {status}, {status_text}, {status_icon}, {category}, {anchor}, {tested_on}, {os_build}, {device_model}, {user_profile} and {tester} are replaced with the app's values. {tags} becomes the app's tags quoted and separated by commas, so tags = [{tags}] puts the page in a taxonomy.",
+                    "type": ["string", "null"],
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    // read `template_file` if there is one
+    pub fn load_template(&mut self, config_folder: &Path) -> Result<(), String> {
+        let Some(template_file) = &self.template_file else {
+            return Ok(());
+        };
+
+        let path = config_folder.join(template_file);
+        let template = read_to_string(&path)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+
+        // the page adds its own line breaks after it
+        self.template = Some(template.trim_end_matches('\n').to_string());
+        Ok(())
+    }
+
+    // front matter from the template, then the app's card
+    // `category` is the one the list uses, which might come from the app's folder
+    pub fn write_page(
+        &self,
+        app: &AppCompatApp,
+        category: Option<&str>,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let template = self.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+
+        write!(
+            w,
+            "{}\n{}\n\n",
+            fill_template(template, app, category),
+            GENERATED_PAGE_MARKER
+        )?;
+        app.write_card_line(w)?;
+        writeln!(w)
+    }
+}
+
+fn fill_template(template: &str, app: &AppCompatApp, category: Option<&str>) -> String {
+    [
+        ("{app_name}", app.app_name.clone()),
+        ("{package_name}", app.package_name.clone()),
+        ("{version}", app.version.clone()),
+        ("{status}", app.status.key().to_string()),
+        ("{status_text}", app.status.to_string()),
+        ("{status_icon}", app.status.icon().to_string()),
+        ("{category}", category.unwrap_or_default().to_string()),
+        ("{anchor}", app.anchor()),
+        ("{tested_on}", app.tested_on.to_string()),
+        ("{os_build}", app.os_build.to_string()),
+        ("{device_model}", app.device_model.to_string()),
+        (
+            "{user_profile}",
+            app.user_profile
+                .map(|profile| profile.to_string())
+                .unwrap_or_default(),
+        ),
+        ("{tester}", app.tester.to_string()),
+        (
+            "{tags}",
+            app.tags
+                .iter()
+                .map(|tag| format!("\"{}\"", tag))
+                .collect::<Vec<String>>()
+                .join(", "),
+        ),
+    ]
+    .iter()
+    .fold(template.to_string(), |text, (placeholder, value)| {
+        text.replace(placeholder, value)
+    })
+}
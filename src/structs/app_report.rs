@@ -0,0 +1,154 @@
+use crate::output_functions::escape_html;
+use crate::stdin_functions::{
+    get_choice_from_user::get_choice_from_user,
+    get_option_date_from_user::get_option_date_from_user,
+    get_option_string_from_user::get_option_string_from_user,
+    get_string_from_user::get_string_from_user,
+};
+use crate::structs::app_compat_app::{
+    get_user_profile_from_user, DEVICE_MODEL_TEXT, OS_BUILD_TEXT, STATUS_TEXT, TESTED_ON_TEXT,
+    TESTER_TEXT, USER_PROFILE_TEXT, VERSION_TEXT,
+};
+use crate::structs::compat_status::CompatStatus;
+use crate::structs::string_or_none::StringOrNone;
+use crate::structs::user_profile::UserProfile;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const REPORT_COMMENT_TEXT: &str = "Any comments about this test?";
+
+// one dated test result, an app file can have any number of them in `reports`
+// the optional fields are left out of the file when they're empty, to keep the list short
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AppReport {
+    pub tested_on: String,
+    pub version: String,
+    pub status: CompatStatus,
+    #[serde(default, skip_serializing_if = "StringOrNone::is_none")]
+    pub comment: StringOrNone,
+    #[serde(default, skip_serializing_if = "StringOrNone::is_none")]
+    pub tester: StringOrNone,
+    #[serde(default, skip_serializing_if = "StringOrNone::is_none")]
+    pub os_build: StringOrNone,
+    #[serde(default, skip_serializing_if = "StringOrNone::is_none")]
+    pub device_model: StringOrNone,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_profile: Option<UserProfile>,
+}
+
+impl AppReport {
+    // ask for a new report on stdin, used by `--add-report`
+    pub fn new_from_command_line() -> Result<Self, String> {
+        // unlike the app's own `tested_on`, a report always has a date
+        let tested_on = loop {
+            match get_option_date_from_user(&format!("[required] {}:", TESTED_ON_TEXT))? {
+                StringOrNone(Some(date)) => break date,
+                StringOrNone(None) => println!("Invalid (too short). Try again."),
+            }
+        };
+
+        let version = get_string_from_user(&format!("[required] {}:", VERSION_TEXT), false)?;
+
+        let status = CompatStatus::ALL[get_choice_from_user(
+            &format!("[required] {}", STATUS_TEXT),
+            &CompatStatus::ALL.map(|status| status.to_string()),
+        )?];
+
+        let comment = get_option_string_from_user(&format!("[optional] {}", REPORT_COMMENT_TEXT))?;
+        let tester = get_option_string_from_user(&format!("[optional] {}:", TESTER_TEXT))?;
+        let os_build = get_option_string_from_user(&format!("[optional] {}:", OS_BUILD_TEXT))?;
+        let device_model =
+            get_option_string_from_user(&format!("[optional] {}:", DEVICE_MODEL_TEXT))?;
+        let user_profile = get_user_profile_from_user()?;
+
+        Ok(Self {
+            tested_on,
+            version,
+            status,
+            comment,
+            tester,
+            os_build,
+            device_model,
+            user_profile,
+        })
+    }
+
+    pub fn json_schema(description: &str) -> Value {
+        json!({
+            "description": description,
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "tested_on": {
+                        "description": TESTED_ON_TEXT,
+                        "type": "string",
+                        "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}$",
+                    },
+                    "version": { "description": VERSION_TEXT, "type": "string", "minLength": 1 },
+                    "status": CompatStatus::json_schema(STATUS_TEXT),
+                    "comment": StringOrNone::json_schema(REPORT_COMMENT_TEXT),
+                    "tester": StringOrNone::json_schema(TESTER_TEXT),
+                    "os_build": StringOrNone::json_schema(OS_BUILD_TEXT),
+                    "device_model": StringOrNone::json_schema(DEVICE_MODEL_TEXT),
+                    "user_profile": UserProfile::json_schema(USER_PROFILE_TEXT),
+                },
+                "required": ["tested_on", "version", "status"],
+                "additionalProperties": false,
+            },
+        })
+    }
+
+    // one line of the history, like `2024-05-31 · 1.2.3 · ✅ Works · by @someone`
+    // the comment comes after it, it's html already (see `replace_new_lines_with_p_tags`)
+    // `quote` is the quote used for attributes, the card argument can only use single quotes
+    pub fn history_item_html(&self, quote: char) -> String {
+        let mut parts = vec![
+            escape_html(&self.tested_on),
+            escape_html(&self.version),
+            format!("{} {}", self.status.icon(), self.status),
+        ];
+        if let StringOrNone(Some(device_model)) = &self.device_model {
+            parts.push(escape_html(device_model));
+        }
+        if let StringOrNone(Some(os_build)) = &self.os_build {
+            parts.push(format!("build {}", escape_html(os_build)));
+        }
+        if let Some(user_profile) = self.user_profile {
+            parts.push(user_profile.to_string());
+        }
+        if let StringOrNone(Some(tester)) = &self.tester {
+            parts.push(format!("by {}", escape_html(tester)));
+        }
+
+        format!(
+            "<li class={q}{}{q}>{}{}</li>",
+            self.status.key(),
+            parts.join(" · "),
+            self.comment
+                .0
+                .as_ref()
+                .map(|comment| format!("<div class={q}comment{q}>{}</div>", comment, q = quote))
+                .unwrap_or_default(),
+            q = quote
+        )
+    }
+
+    pub fn trim_all_string_fields(&mut self) {
+        self.tested_on = self.tested_on.trim().to_string();
+        self.version = self.version.trim().to_string();
+        self.comment = self.comment.trimmed();
+        self.tester = self.tester.trimmed();
+        self.os_build = self.os_build.trimmed();
+        self.device_model = self.device_model.trimmed();
+    }
+
+    pub fn replace_double_quotes_from_all_string_fields(&mut self) {
+        self.version = self.version.replace("\"", "'");
+        self.comment = self.comment.replace_double_quotes_with_single_quotes();
+        self.tester = self.tester.replace_double_quotes_with_single_quotes();
+        self.os_build = self.os_build.replace_double_quotes_with_single_quotes();
+        self.device_model = self.device_model.replace_double_quotes_with_single_quotes();
+    }
+}
@@ -1,9 +1,48 @@
-use serde::{Deserialize, Serialize};
+use crate::stdin_functions::answer_string_to_bool;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+#[derive(Debug, Serialize, Copy, Clone, Default)]
 pub struct BoolOrNone(pub Option<bool>);
 
+// hand-edited files sometimes have `yes`/`no` (which yaml 1.2 reads as strings) instead of
+// `true`/`false`, so those are accepted too, the same way they are when adding an app
+impl<'de> Deserialize<'de> for BoolOrNone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum BoolOrText {
+            Bool(bool),
+            Text(String),
+        }
+
+        let b = match Option::<BoolOrText>::deserialize(deserializer)? {
+            None => None,
+            Some(BoolOrText::Bool(b)) => Some(b),
+            Some(BoolOrText::Text(text)) => match text.trim().to_lowercase().as_str() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => Some(answer_string_to_bool(text.clone()).map_err(|_| {
+                    D::Error::custom(format!("expected true, false or null, found `{}`", text))
+                })?),
+            },
+        };
+
+        Ok(BoolOrNone(b))
+    }
+}
+
+impl BoolOrNone {
+    pub fn json_schema(description: &str) -> Value {
+        json!({
+            "description": format!("{} (null if it's unknown)", description),
+            "type": ["boolean", "null"],
+        })
+    }
+}
+
 impl Display for BoolOrNone {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let r = if let Some(b) = self.0 {
@@ -0,0 +1,107 @@
+use crate::structs::app_compat_app::AppCompatApp;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+// bump this whenever the rendered cards change, so old caches aren't used anymore
+const CACHE_VERSION: u32 = 7;
+
+// everything that's remembered about one app file between runs
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CachedApp {
+    // hash of the app file's contents
+    pub hash: String,
+    // the app after it was cleaned up for printing
+    pub app: AppCompatApp,
+    // the rendered `app_compat_card` shortcode
+    pub card: String,
+}
+
+// on-disk cache so repeated builds only parse and render the app files that changed
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct BuildCache {
+    // hash of the output config, this program's version and `CACHE_VERSION`
+    // if any of them changes, nothing in the cache can be trusted anymore
+    config_hash: String,
+    // keyed by the app file's path relative to the app files folder
+    apps: BTreeMap<String, CachedApp>,
+    // `--no-cache` turns this off so nothing is reused or rendered ahead of time
+    #[serde(skip)]
+    enabled: bool,
+}
+
+impl BuildCache {
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    // load the cache from the previous run
+    // a missing, broken or outdated cache just means starting from scratch
+    pub fn load(path: &Path, config_file_contents: &[u8]) -> Self {
+        let config_hash = hash_bytes(
+            &[
+                env!("CARGO_PKG_VERSION").as_bytes(),
+                b"\n",
+                CACHE_VERSION.to_string().as_bytes(),
+                b"\n",
+                config_file_contents,
+            ]
+            .concat(),
+        );
+
+        let cache = File::open(path).ok().and_then(|file| {
+            serde_yaml::from_reader::<_, Self>(file)
+                .map_err(|e| eprintln!("Ignoring broken cache {}: {}", path.display(), e))
+                .ok()
+        });
+
+        match cache {
+            Some(cache) if cache.config_hash == config_hash => Self {
+                enabled: true,
+                ..cache
+            },
+            _ => Self {
+                config_hash,
+                apps: BTreeMap::new(),
+                enabled: true,
+            },
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // get the cached app if the file hasn't changed since it was cached
+    pub fn get_app(&self, file: &str, hash: &str) -> Option<&CachedApp> {
+        self.apps
+            .get(file)
+            .filter(|cached| self.enabled && cached.hash == hash)
+    }
+
+    // replace everything with the apps from this run,
+    // so deleted files don't stay in the cache forever
+    pub fn set_apps(&mut self, apps: BTreeMap<String, CachedApp>) {
+        self.apps = apps;
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let file = File::create(path)
+            .map_err(|e| format!("unable to create cache {}: {}", path.display(), e))?;
+        serde_yaml::to_writer(file, self)
+            .map_err(|e| format!("unable to write cache {}: {}", path.display(), e))
+    }
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
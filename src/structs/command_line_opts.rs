@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug, Clone)]
@@ -7,20 +7,79 @@ pub struct CommandLineOpts {
     /// Use --add to create a new app config file.
     #[structopt(long)]
     pub add: bool,
+    /// Use --add-report to add a dated test result to an existing app file.
+    /// It asks for the package name, the file is <package_name>.yaml in the app files folder,
+    /// or in a subfolder when the --yaml-file config scans recursively.
+    #[structopt(long)]
+    pub add_report: bool,
     /// Use this to run the thing and create the file.
     #[structopt(long)]
     pub run: bool,
+    /// Use this to check that the .md file is up to date without writing to it.
+    /// Prints a diff and exits with an error if it isn't.
+    #[structopt(long)]
+    pub check: bool,
+    /// Use this to rebuild the .md file every time an app file or the config changes.
+    #[structopt(long)]
+    pub watch: bool,
+    /// Use this to rewrite app files with an older schema_version to the current one.
+    #[structopt(long)]
+    pub migrate: bool,
+    /// Use this to rewrite all app files in the same layout --add uses.
+    /// With --check, only show which files aren't formatted.
+    #[structopt(long)]
+    pub fmt: bool,
+    /// Use this to print the json schema for app files (app) or the config file (config),
+    /// so editors can autocomplete and check them.
+    #[structopt(long, possible_values = &["app", "config"])]
+    pub schema: Option<String>,
+    /// Use this to preview the list in a browser while editing app files.
+    #[structopt(long)]
+    pub serve: bool,
+    /// The port the preview server listens on.
+    #[structopt(long, default_value = "8000")]
+    pub port: u16,
 
     /// An input yaml for creating the .md files.
-    /// Required for run and check.
+    /// Required for run, check, watch and serve.
     #[structopt(long, parse(from_os_str))]
     pub yaml_file: Option<PathBuf>,
     /// The folder where all app files are stored.
-    /// It's always required.
-    #[structopt(long, parse(from_os_str))]
-    pub app_files_folder: PathBuf,
+    /// It's always required, except with --schema.
+    #[structopt(long, parse(from_os_str), required_unless = "schema")]
+    pub app_files_folder: Option<PathBuf>,
     /// The file where the resulting .md file will be saved.
-    /// It's only required when using --run.
+    /// It's only required when using --run, --check or --watch.
+    /// Use `-` to print the file to stdout instead.
     #[structopt(long, parse(from_os_str))]
     pub output_md_file: Option<PathBuf>,
+    /// A folder where every app gets its own .md page, plus an _index.md linking them.
+    /// Pages of apps that were deleted are removed. Used with --run, --check and --watch.
+    #[structopt(long, parse(from_os_str))]
+    pub output_dir: Option<PathBuf>,
+    /// Also save a json file with the anchor of every app's card, by package name.
+    /// Used with --run and --check.
+    #[structopt(long, parse(from_os_str))]
+    pub permalink_index: Option<PathBuf>,
+
+    /// Don't report files in the app files folder that are skipped.
+    #[structopt(long)]
+    pub quiet: bool,
+    /// Don't use the build cache, parse and render every app file again.
+    #[structopt(long)]
+    pub no_cache: bool,
+    /// Where the build cache is saved between runs.
+    #[structopt(
+        long,
+        parse(from_os_str),
+        default_value = ".create_md_pages_cache.yaml"
+    )]
+    pub cache_file: PathBuf,
+}
+
+impl CommandLineOpts {
+    // only missing with `--schema`, which doesn't need it
+    pub fn app_files_folder(&self) -> &Path {
+        self.app_files_folder.as_deref().unwrap_or(Path::new(""))
+    }
 }
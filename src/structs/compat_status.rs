@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+
+// how well an app works, shown as the big icon on each card
+//
+// older app files only had `works` and `works_without_compat_mode`, those map like this:
+// - works: true,  works_without_compat_mode: true  -> works
+// - works: true,  works_without_compat_mode: false -> works_with_compat_mode
+// - works: false, works_without_compat_mode: any   -> does_not_work
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatStatus {
+    Works,
+    WorksWithCompatMode,
+    WorksWithWorkaround,
+    PartiallyWorks,
+    DoesNotWork,
+    UntestedOnCurrentRelease,
+}
+
+impl CompatStatus {
+    // in the order they're offered when adding an app
+    pub const ALL: [CompatStatus; 6] = [
+        CompatStatus::Works,
+        CompatStatus::WorksWithCompatMode,
+        CompatStatus::WorksWithWorkaround,
+        CompatStatus::PartiallyWorks,
+        CompatStatus::DoesNotWork,
+        CompatStatus::UntestedOnCurrentRelease,
+    ];
+
+    // every status with its description, so editors can show what they mean
+    pub fn json_schema(description: &str) -> Value {
+        json!({
+            "description": description,
+            "oneOf": CompatStatus::ALL
+                .iter()
+                .map(|status| json!({
+                    "const": status.key(),
+                    "description": format!("{} {}", status.icon(), status),
+                }))
+                .collect::<Vec<Value>>(),
+        })
+    }
+
+    pub fn from_legacy_bools(works: bool, works_without_compat_mode: bool) -> Self {
+        match (works, works_without_compat_mode) {
+            (true, true) => CompatStatus::Works,
+            (true, false) => CompatStatus::WorksWithCompatMode,
+            _ => CompatStatus::DoesNotWork,
+        }
+    }
+
+    // whether the app can be used at all, this is the old `works`
+    pub fn works(&self) -> bool {
+        matches!(
+            self,
+            CompatStatus::Works
+                | CompatStatus::WorksWithCompatMode
+                | CompatStatus::WorksWithWorkaround
+                | CompatStatus::PartiallyWorks
+        )
+    }
+
+    // how bad it is, used to pick the worst of several reports
+    // untested says nothing about how well it works, so it only wins if nothing else was reported
+    pub fn severity(&self) -> u8 {
+        match self {
+            CompatStatus::UntestedOnCurrentRelease => 0,
+            CompatStatus::Works => 1,
+            CompatStatus::WorksWithCompatMode => 2,
+            CompatStatus::WorksWithWorkaround => 3,
+            CompatStatus::PartiallyWorks => 4,
+            CompatStatus::DoesNotWork => 5,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            CompatStatus::Works => "✅",
+            CompatStatus::WorksWithCompatMode => "⚠️",
+            CompatStatus::WorksWithWorkaround => "🛠️",
+            CompatStatus::PartiallyWorks => "🟡",
+            CompatStatus::DoesNotWork => "❌",
+            CompatStatus::UntestedOnCurrentRelease => "❓",
+        }
+    }
+
+    // the same name that's used in the yaml files
+    pub fn key(&self) -> &'static str {
+        match self {
+            CompatStatus::Works => "works",
+            CompatStatus::WorksWithCompatMode => "works_with_compat_mode",
+            CompatStatus::WorksWithWorkaround => "works_with_workaround",
+            CompatStatus::PartiallyWorks => "partially_works",
+            CompatStatus::DoesNotWork => "does_not_work",
+            CompatStatus::UntestedOnCurrentRelease => "untested_on_current_release",
+        }
+    }
+}
+
+impl Display for CompatStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let r = match self {
+            CompatStatus::Works => "Works",
+            CompatStatus::WorksWithCompatMode => "Works with Exploit Protection Compatibility Mode",
+            CompatStatus::WorksWithWorkaround => "Works with a workaround",
+            CompatStatus::PartiallyWorks => "Partially works",
+            CompatStatus::DoesNotWork => "Doesn't work",
+            CompatStatus::UntestedOnCurrentRelease => "Untested on the current release",
+        };
+        write!(f, "{}", r)
+    }
+}
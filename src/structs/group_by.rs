@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// how the cards are split into sections, each with its own entry in the toc
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    // the first letter of the app's name
+    #[default]
+    Letter,
+    // the app's `category`, or the folder its file is in
+    Category,
+}
+
+impl GroupBy {
+    pub fn json_schema() -> Value {
+        json!({
+            "description": "How the cards are grouped: by the first letter of the app's name, or by category (the app's category field, or the folder its file is in)",
+            "enum": ["letter", "category"],
+            "default": "letter",
+        })
+    }
+}
@@ -1,6 +1,22 @@
 pub mod app_compat_app;
 pub mod app_compat_list;
+pub mod app_file_scan_options;
+pub mod app_filter;
+pub mod app_link;
 pub mod app_output_md_file_config;
+pub mod app_page_options;
+pub mod app_report;
 pub mod bool_or_none;
+pub mod build_cache;
 pub mod command_line_opts;
+pub mod compat_status;
+pub mod group_by;
+pub mod report_aggregation;
+pub mod sort_options;
+pub mod split_options;
 pub mod string_or_none;
+pub mod tag_options;
+pub mod user_profile;
+
+// every json schema made by `--schema` uses this draft
+pub const JSON_SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
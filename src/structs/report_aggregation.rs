@@ -0,0 +1,226 @@
+use crate::structs::app_report::AppReport;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// which report a card shows when an app has several
+// only the reports for the newest version count, older versions were fixed or broken since
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportAggregation {
+    // the newest report
+    #[default]
+    Latest,
+    // the status most reports agree on, the newest one wins a tie
+    Majority,
+    // the worst status anyone reported
+    WorstCase,
+}
+
+impl ReportAggregation {
+    pub fn json_schema() -> Value {
+        json!({
+            "description": "Which report a card shows when an app has several for its newest version: the latest one, the status most reports agree on, or the worst status anyone reported",
+            "enum": ["latest", "majority", "worst_case"],
+            "default": "latest",
+        })
+    }
+
+    // the report to show, the newest one with the picked status
+    // `reports` are in the order they're in the file
+    pub fn pick<'a>(&self, reports: &'a [AppReport]) -> Option<&'a AppReport> {
+        let latest = newest(reports.iter())?;
+        let same_version = reports
+            .iter()
+            .filter(|report| report.version == latest.version)
+            .collect::<Vec<&AppReport>>();
+
+        let status = match self {
+            ReportAggregation::Latest => return Some(latest),
+            ReportAggregation::Majority => {
+                // ties go to the status whose newest report is the newest
+                let count = |report: &AppReport| {
+                    same_version
+                        .iter()
+                        .filter(|other| other.status == report.status)
+                        .count()
+                };
+                let most = same_version.iter().map(|report| count(report)).max()?;
+                newest(
+                    same_version
+                        .iter()
+                        .copied()
+                        .filter(|report| count(report) == most),
+                )?
+                .status
+            }
+            ReportAggregation::WorstCase => same_version
+                .iter()
+                .map(|report| report.status)
+                .max_by_key(|status| status.severity())?,
+        };
+
+        newest(
+            same_version
+                .into_iter()
+                .filter(|report| report.status == status),
+        )
+    }
+}
+
+// the newest report, reports from the same day count in the order they're in the file
+pub fn newest<'a>(reports: impl Iterator<Item = &'a AppReport>) -> Option<&'a AppReport> {
+    reports
+        .enumerate()
+        .max_by(|(i, a), (j, b)| a.tested_on.cmp(&b.tested_on).then(i.cmp(j)))
+        .map(|(_, report)| report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::compat_status::CompatStatus::{self, *};
+    use crate::structs::string_or_none::StringOrNone;
+
+    // the tester tells the reports apart
+    fn report(tester: &str, tested_on: &str, version: &str, status: CompatStatus) -> AppReport {
+        AppReport {
+            tested_on: tested_on.to_string(),
+            version: version.to_string(),
+            status,
+            comment: StringOrNone(None),
+            tester: StringOrNone(Some(tester.to_string())),
+            os_build: StringOrNone(None),
+            device_model: StringOrNone(None),
+            user_profile: None,
+        }
+    }
+
+    fn picked(aggregation: ReportAggregation, reports: &[AppReport]) -> Option<String> {
+        aggregation
+            .pick(reports)
+            .and_then(|report| report.tester.0.clone())
+    }
+
+    #[test]
+    fn nothing_is_picked_without_reports() {
+        for aggregation in [
+            ReportAggregation::Latest,
+            ReportAggregation::Majority,
+            ReportAggregation::WorstCase,
+        ] {
+            assert_eq!(picked(aggregation, &[]), None);
+        }
+    }
+
+    #[test]
+    fn newest_goes_by_date_then_by_position_in_the_file() {
+        let reports = [
+            report("a", "2024-03-01", "2", Works),
+            report("b", "2024-01-01", "1", Works),
+            report("c", "2024-03-01", "2", DoesNotWork),
+            report("d", "2024-02-01", "2", Works),
+        ];
+
+        assert_eq!(
+            newest(reports.iter()).and_then(|report| report.tester.0.clone()),
+            Some("c".to_string())
+        );
+        assert_eq!(
+            picked(ReportAggregation::Latest, &reports),
+            Some("c".to_string())
+        );
+    }
+
+    #[test]
+    fn only_the_newest_version_counts() {
+        let reports = [
+            report("a", "2024-01-01", "1", DoesNotWork),
+            report("b", "2024-01-02", "1", DoesNotWork),
+            report("c", "2024-02-01", "2", Works),
+        ];
+
+        assert_eq!(
+            picked(ReportAggregation::Majority, &reports),
+            Some("c".to_string())
+        );
+        assert_eq!(
+            picked(ReportAggregation::WorstCase, &reports),
+            Some("c".to_string())
+        );
+    }
+
+    #[test]
+    fn majority_picks_the_newest_report_with_the_most_common_status() {
+        let reports = [
+            report("a", "2024-01-01", "2", Works),
+            report("b", "2024-01-03", "2", Works),
+            report("c", "2024-01-04", "2", DoesNotWork),
+            report("d", "2024-01-02", "2", Works),
+        ];
+
+        assert_eq!(
+            picked(ReportAggregation::Majority, &reports),
+            Some("b".to_string())
+        );
+    }
+
+    #[test]
+    fn majority_ties_go_to_the_newest_report() {
+        let reports = [
+            report("a", "2024-01-01", "2", Works),
+            report("b", "2024-01-02", "2", DoesNotWork),
+            report("c", "2024-01-03", "2", Works),
+            report("d", "2024-01-04", "2", DoesNotWork),
+        ];
+        assert_eq!(
+            picked(ReportAggregation::Majority, &reports),
+            Some("d".to_string())
+        );
+
+        // on the same day, the one further down in the file is the newest
+        let reports = [
+            report("a", "2024-01-01", "2", DoesNotWork),
+            report("b", "2024-01-01", "2", Works),
+        ];
+        assert_eq!(
+            picked(ReportAggregation::Majority, &reports),
+            Some("b".to_string())
+        );
+    }
+
+    #[test]
+    fn worst_case_picks_the_newest_report_with_the_worst_status() {
+        let reports = [
+            report("a", "2024-01-01", "2", PartiallyWorks),
+            report("b", "2024-01-03", "2", Works),
+            report("c", "2024-01-02", "2", PartiallyWorks),
+            report("d", "2024-01-02", "2", WorksWithWorkaround),
+        ];
+
+        assert_eq!(
+            picked(ReportAggregation::WorstCase, &reports),
+            Some("c".to_string())
+        );
+    }
+
+    #[test]
+    fn worst_case_only_picks_untested_when_nothing_else_was_reported() {
+        let reports = [
+            report("a", "2024-01-01", "2", Works),
+            report("b", "2024-01-02", "2", UntestedOnCurrentRelease),
+        ];
+        assert_eq!(
+            picked(ReportAggregation::WorstCase, &reports),
+            Some("a".to_string())
+        );
+
+        let reports = [
+            report("a", "2024-01-01", "2", UntestedOnCurrentRelease),
+            report("b", "2024-01-01", "2", UntestedOnCurrentRelease),
+        ];
+        assert_eq!(
+            picked(ReportAggregation::WorstCase, &reports),
+            Some("b".to_string())
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use deunicode::deunicode_char;
+use icu_collator::{Collator, CollatorOptions, Strength};
+use icu_locid::Locale;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// the heading for names that don't start with a letter from a to z
+pub const SYMBOLS_GROUP: &str = "#";
+
+// how apps are sorted and which letter they're listed under
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SortOptions {
+    // whose alphabet is used for sorting, like `en` or `sv`
+    pub locale: String,
+    // list `Émile` under E instead of under É
+    // letters that are part of the locale's alphabet, like `Ö` for `sv`, always get their own heading
+    pub fold_accents: bool,
+    // list names starting with digits, symbols, emoji or other scripts under `#`
+    // instead of giving each its own heading
+    pub group_symbols: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            locale: "en".to_string(),
+            fold_accents: true,
+            group_symbols: true,
+        }
+    }
+}
+
+impl SortOptions {
+    pub fn json_schema() -> Value {
+        let defaults = Self::default();
+
+        json!({
+            "description": "How apps are sorted and which letter they're listed under",
+            "type": "object",
+            "properties": {
+                "locale": {
+                    "description": "Whose alphabet is used for sorting, like en or sv",
+                    "type": "string",
+                    "default": defaults.locale,
+                },
+                "fold_accents": {
+                    "description": "List names starting with accented letters under the base letter, like É under E. Letters the locale has in its alphabet, like Ö for sv, keep their own heading",
+                    "type": "boolean",
+                    "default": defaults.fold_accents,
+                },
+                "group_symbols": {
+                    "description": "List names starting with digits, symbols, emoji or other scripts under #",
+                    "type": "boolean",
+                    "default": defaults.group_symbols,
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    // compares strings the way people using `locale` expect
+    pub fn collator(&self) -> Result<Collator, String> {
+        self.collator_with(CollatorOptions::new())
+    }
+
+    // only compares base letters, so `é` and `e` are the same letter
+    // unless the locale says they aren't, like `ö` and `o` for `sv`
+    pub fn letter_collator(&self) -> Result<Collator, String> {
+        let mut options = CollatorOptions::new();
+        options.strength = Some(Strength::Primary);
+        self.collator_with(options)
+    }
+
+    fn collator_with(&self, options: CollatorOptions) -> Result<Collator, String> {
+        let locale = self
+            .locale
+            .parse::<Locale>()
+            .map_err(|e| format!("invalid locale `{}`: {}", self.locale, e))?;
+
+        Collator::try_new(&(&locale).into(), options)
+            .map_err(|e| format!("unable to sort for locale `{}`: {}", self.locale, e))
+    }
+
+    // the heading an app with this name is listed under
+    // `letters` is the `letter_collator`
+    pub fn letter_of(&self, name: &str, letters: &Collator) -> String {
+        let Some(first) = name.trim_start().chars().next() else {
+            return SYMBOLS_GROUP.to_string();
+        };
+
+        let folded = deunicode_char(first).and_then(|folded| folded.chars().next());
+        let first = match folded {
+            Some(folded)
+                if self.fold_accents
+                    && is_latin(first)
+                    && letters
+                        .compare(&first.to_string(), &folded.to_string())
+                        .is_eq() =>
+            {
+                folded
+            }
+            _ => first,
+        };
+
+        let is_letter = first.is_ascii_alphabetic() || (is_latin(first) && first.is_alphabetic());
+        if self.group_symbols && !is_letter {
+            SYMBOLS_GROUP.to_string()
+        } else {
+            first.to_uppercase().to_string()
+        }
+    }
+}
+
+// accented latin letters like é, ø or ł
+// only these are folded, other scripts don't have a base letter from a to z
+fn is_latin(c: char) -> bool {
+    matches!(c, '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letter_of(sort: &SortOptions, name: &str) -> String {
+        sort.letter_of(name, &sort.letter_collator().unwrap())
+    }
+
+    #[test]
+    fn accents_are_folded_unless_the_locale_has_the_letter() {
+        let en = SortOptions::default();
+        assert_eq!(letter_of(&en, "Émile"), "E");
+        assert_eq!(letter_of(&en, "Öffi"), "O");
+        assert_eq!(letter_of(&en, "  łódź"), "L");
+
+        let sv = SortOptions {
+            locale: "sv".to_string(),
+            ..SortOptions::default()
+        };
+        assert_eq!(letter_of(&sv, "Öffi"), "Ö");
+        assert_eq!(letter_of(&sv, "Åka"), "Å");
+        assert_eq!(letter_of(&sv, "Émile"), "E");
+
+        let unfolded = SortOptions {
+            fold_accents: false,
+            ..SortOptions::default()
+        };
+        assert_eq!(letter_of(&unfolded, "émile"), "É");
+    }
+
+    #[test]
+    fn digits_emoji_and_other_scripts_are_grouped() {
+        let grouped = SortOptions::default();
+        for name in ["1Password", "🚀 Rocket", "微信", "Яндекс", "", "   "] {
+            assert_eq!(letter_of(&grouped, name), SYMBOLS_GROUP, "{}", name);
+        }
+
+        let ungrouped = SortOptions {
+            group_symbols: false,
+            ..SortOptions::default()
+        };
+        assert_eq!(letter_of(&ungrouped, "1Password"), "1");
+        assert_eq!(letter_of(&ungrouped, "微信"), "微");
+        assert_eq!(letter_of(&ungrouped, "яндекс"), "Я");
+        assert_eq!(letter_of(&ungrouped, ""), SYMBOLS_GROUP);
+    }
+}
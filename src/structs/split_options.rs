@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// how the list is spread over several pages
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitBy {
+    // everything on one page
+    #[default]
+    None,
+    // a page for every letter or category
+    Group,
+    // a page for every `apps_per_page` apps
+    Count,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SplitOptions {
+    pub by: SplitBy,
+    // only used with `count`
+    pub apps_per_page: usize,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            by: SplitBy::None,
+            apps_per_page: 50,
+        }
+    }
+}
+
+impl SplitOptions {
+    pub fn json_schema() -> Value {
+        let defaults = Self::default();
+
+        json!({
+            "description": "Spread the list over several pages, which are much faster to render than one big page",
+            "type": "object",
+            "properties": {
+                "by": {
+                    "description": "none keeps everything on one page, group makes a page for every letter or category and count a page for every apps_per_page apps",
+                    "enum": ["none", "group", "count"],
+                    "default": "none",
+                },
+                "apps_per_page": {
+                    "description": "How many apps are on every page when splitting by count",
+                    "type": "integer",
+                    "minimum": 1,
+                    "default": defaults.apps_per_page,
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+}
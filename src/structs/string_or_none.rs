@@ -1,10 +1,22 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct StringOrNone(pub Option<String>);
 
 impl StringOrNone {
+    pub fn json_schema(description: &str) -> Value {
+        json!({
+            "description": format!("{} (null if there's nothing to add)", description),
+            "type": ["string", "null"],
+        })
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+
     pub fn replace_double_quotes_with_single_quotes(&self) -> Self {
         if let Self(Some(text)) = self {
             Self(Some(text.replace("\"", "'")))
@@ -13,6 +25,28 @@ impl StringOrNone {
         }
     }
 
+    // no whitespace at the start or end, or at the end of any line,
+    // and an empty text is the same as no text at all
+    pub fn trimmed(&self) -> Self {
+        match self {
+            Self(Some(text)) => {
+                let text = text
+                    .trim()
+                    .split('\n')
+                    .map(|line| line.trim_end())
+                    .collect::<Vec<&str>>()
+                    .join("\n");
+
+                if text.is_empty() {
+                    Self(None)
+                } else {
+                    Self(Some(text))
+                }
+            }
+            Self(None) => Self(None),
+        }
+    }
+
     pub fn replace_new_lines_with_p_tags(&self) -> Self {
         if let Self(Some(text)) = self {
             let text = text.trim();
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+// what's done with the apps' `tags`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct TagOptions {
+    // yaml file with every tag that can be used and its title, like `banking: Banking`
+    // the path is relative to the config
+    // without one, any tag can be used
+    pub vocabulary_file: Option<PathBuf>,
+    // the zola taxonomy the page's tags are added to in the front matter
+    pub taxonomy: Option<String>,
+    // add a section for every tag after the list
+    pub sections: bool,
+    // loaded from `vocabulary_file`
+    #[serde(skip)]
+    pub vocabulary: Option<BTreeMap<String, String>>,
+}
+
+impl TagOptions {
+    pub fn json_schema() -> Value {
+        json!({
+            "description": "What's done with the apps' tags",
+            "type": "object",
+            "properties": {
+                "vocabulary_file": {
+                    "description": "Yaml file (relative to this config) with every tag that can be used and its title, like banking: Banking. Without one, any tag can be used.",
+                    "type": ["string", "null"],
+                },
+                "taxonomy": {
+                    "description": "The zola taxonomy the page's tags are added to in the front matter, like tags",
+                    "type": ["string", "null"],
+                },
+                "sections": {
+                    "description": "Add a section listing the apps for every tag after the list",
+                    "type": "boolean",
+                    "default": false,
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    // read `vocabulary_file` if there is one
+    pub fn load_vocabulary(&mut self, config_folder: &Path) -> Result<(), String> {
+        let Some(vocabulary_file) = &self.vocabulary_file else {
+            return Ok(());
+        };
+
+        let path = config_folder.join(vocabulary_file);
+        let file =
+            File::open(&path).map_err(|e| format!("unable to open {}: {}", path.display(), e))?;
+        let vocabulary = serde_yaml::from_reader(file)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+
+        self.vocabulary = Some(vocabulary);
+        Ok(())
+    }
+
+    // the title from the vocabulary, or the tag itself
+    pub fn title<'a>(&'a self, tag: &'a str) -> &'a str {
+        self.vocabulary
+            .as_ref()
+            .and_then(|vocabulary| vocabulary.get(tag))
+            .map(String::as_str)
+            .unwrap_or(tag)
+    }
+}
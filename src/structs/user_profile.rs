@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+
+// which kind of profile the app was tested in, some apps behave differently outside the owner
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserProfile {
+    Owner,
+    SecondaryUser,
+    WorkProfile,
+    PrivateSpace,
+}
+
+impl UserProfile {
+    // in the order they're offered when adding an app
+    pub const ALL: [UserProfile; 4] = [
+        UserProfile::Owner,
+        UserProfile::SecondaryUser,
+        UserProfile::WorkProfile,
+        UserProfile::PrivateSpace,
+    ];
+
+    pub fn json_schema(description: &str) -> Value {
+        json!({
+            "description": format!("{} (null if it's not known)", description),
+            "enum": UserProfile::ALL
+                .iter()
+                .map(|profile| json!(profile.key()))
+                .chain(std::iter::once(Value::Null))
+                .collect::<Vec<Value>>(),
+        })
+    }
+
+    // the same name that's used in the yaml files
+    pub fn key(&self) -> &'static str {
+        match self {
+            UserProfile::Owner => "owner",
+            UserProfile::SecondaryUser => "secondary_user",
+            UserProfile::WorkProfile => "work_profile",
+            UserProfile::PrivateSpace => "private_space",
+        }
+    }
+}
+
+impl Display for UserProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let r = match self {
+            UserProfile::Owner => "Owner",
+            UserProfile::SecondaryUser => "Secondary user",
+            UserProfile::WorkProfile => "Work profile",
+            UserProfile::PrivateSpace => "Private space",
+        };
+        write!(f, "{}", r)
+    }
+}